@@ -0,0 +1,637 @@
+use crate::error::{Error, ErrorKind};
+use crate::model::{Expression, Located, Location, Statement, Token, TokenKind, TokenValue};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Type {
+    Unknown,
+    None,
+    Boolean,
+    Integer,
+    Float,
+    String,
+    List,
+    Record,
+    Function {
+        parameters: Vec<Self>,
+        result: Box<Self>,
+    },
+}
+
+impl Type {
+    fn from_name(token: &Located<Token>) -> Self {
+        match token.node.get_identifier_name().as_deref() {
+            Some("None") => Self::None,
+            Some("Boolean") => Self::Boolean,
+            Some("Integer") => Self::Integer,
+            Some("Float") => Self::Float,
+            Some("String") => Self::String,
+            Some("List") => Self::List,
+            Some("Record") => Self::Record,
+            Some("Function") => Self::function(vec![Self::Unknown], Self::Unknown),
+            _ => Self::Unknown,
+        }
+    }
+
+    fn function(parameters: Vec<Self>, result: Self) -> Self {
+        if parameters.is_empty() {
+            result
+        } else {
+            Self::Function {
+                parameters,
+                result: Box::new(result),
+            }
+        }
+    }
+
+    const fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown | Self::None)
+    }
+
+    const fn is_numeric(&self) -> bool {
+        matches!(self, Self::Integer | Self::Float)
+    }
+
+    fn fits(&self, expected: &Self) -> bool {
+        !self.is_known()
+            || !expected.is_known()
+            || self == expected
+            || (*self == Self::Integer && *expected == Self::Float)
+            || matches!(
+                (self, expected),
+                (Self::Function { .. }, Self::Function { .. })
+            )
+    }
+
+    fn unify(self, other: Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Unknown, _) | (_, Self::Unknown) => Some(Self::Unknown),
+            (Self::None, other) | (other, Self::None) => Some(other),
+            (l, r) if l.is_numeric() && r.is_numeric() && l != r => Some(Self::Float),
+            (l @ Self::Function { .. }, Self::Function { .. }) => Some(l),
+            (l, r) if l == r => Some(l),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown => write!(f, "unknown"),
+            Self::None => write!(f, "None"),
+            Self::Boolean => write!(f, "Boolean"),
+            Self::Integer => write!(f, "Integer"),
+            Self::Float => write!(f, "Float"),
+            Self::String => write!(f, "String"),
+            Self::List => write!(f, "List"),
+            Self::Record => write!(f, "Record"),
+            Self::Function { .. } => write!(f, "Function"),
+        }
+    }
+}
+
+pub fn check(statements: &[Located<Statement>]) -> Vec<Error> {
+    let mut checker = Checker {
+        globals: HashMap::new(),
+        signatures: HashMap::new(),
+        definitions: HashMap::new(),
+        scopes: vec![],
+        errors: vec![],
+    };
+    for statement in statements {
+        checker.declare(statement);
+    }
+    for statement in statements {
+        checker.statement(statement);
+    }
+    checker.errors
+}
+
+struct Checker {
+    globals: HashMap<String, Type>,
+    signatures: HashMap<String, (Vec<Type>, Type)>,
+    definitions: HashMap<String, usize>,
+    scopes: Vec<(String, Type)>,
+    errors: Vec<Error>,
+}
+
+impl Checker {
+    fn declare(&mut self, statement: &Located<Statement>) {
+        match &statement.node {
+            Statement::Attributed { statement, .. } => self.declare(statement),
+            Statement::Mutual { definitions } => {
+                for definition in definitions {
+                    self.declare(definition);
+                }
+            }
+            Statement::Declaration { name, types } => {
+                let (Some(name), Some((result, parameters))) =
+                    (name.node.get_identifier_name(), types.split_last())
+                else {
+                    return;
+                };
+                let parameters: Vec<_> = parameters.iter().map(Type::from_name).collect();
+                let result = Type::from_name(result);
+                self.globals.insert(
+                    name.clone(),
+                    Type::function(parameters.clone(), result.clone()),
+                );
+                self.signatures.insert(name, (parameters, result));
+            }
+            Statement::Definition { name, .. }
+            | Statement::Variable { name, .. }
+            | Statement::Newtype { name } => {
+                if let Some(name) = name.node.get_identifier_name() {
+                    *self.definitions.entry(name).or_default() += 1;
+                }
+            }
+            Statement::Data { constructors, .. } => {
+                for name in constructors
+                    .iter()
+                    .filter_map(|c| c.node.get_identifier_name())
+                {
+                    *self.definitions.entry(name).or_default() += 1;
+                }
+            }
+            Statement::Expression { .. } | Statement::Pragma { .. } | Statement::Use { .. } => {}
+        }
+    }
+
+    fn statement(&mut self, statement: &Located<Statement>) {
+        match &statement.node {
+            Statement::Attributed { statement, .. } => self.statement(statement),
+            Statement::Mutual { definitions } => {
+                for definition in definitions {
+                    self.statement(definition);
+                }
+            }
+            Statement::Definition {
+                name,
+                parameter,
+                body,
+            } => self.definition(name, Some(parameter), body),
+            Statement::Variable { name, body } => self.definition(name, None, body),
+            Statement::Expression { expression } => {
+                self.expression(expression);
+            }
+            Statement::Data { .. }
+            | Statement::Declaration { .. }
+            | Statement::Newtype { .. }
+            | Statement::Pragma { .. }
+            | Statement::Use { .. } => {}
+        }
+    }
+
+    fn definition(
+        &mut self,
+        name: &Located<Token>,
+        parameter: Option<&Located<Token>>,
+        mut body: &Located<Expression>,
+    ) {
+        let Some(name) = name.node.get_identifier_name() else {
+            return;
+        };
+        let declared = self
+            .signatures
+            .get(&name)
+            .map(|(parameters, _)| parameters.len());
+        let (mut parameters, result) = self
+            .signatures
+            .get(&name)
+            .cloned()
+            .map_or((None, Type::Unknown), |(parameters, result)| {
+                (Some(parameters.into_iter()), result)
+            });
+        let mut next = || {
+            parameters
+                .as_mut()
+                .map_or(Some(Type::Unknown), Iterator::next)
+        };
+
+        let scope = self.scopes.len();
+        let mut arity = 0;
+        if let Some(parameter) = parameter {
+            self.bind(parameter, next().unwrap_or(Type::Unknown));
+            arity += 1;
+            while let Expression::Lambda {
+                parameter,
+                body: inner,
+            } = &body.node
+            {
+                self.bind(parameter, next().unwrap_or(Type::Unknown));
+                arity += 1;
+                body = inner;
+            }
+        }
+        let rest: Option<Vec<_>> = parameters.map(Iterator::collect);
+
+        let expected = match &rest {
+            Some(rest) if rest.is_empty() && declared == Some(arity) => result.clone(),
+            _ => Type::Unknown,
+        };
+        let actual = self.expect(body, &expected);
+        self.scopes.truncate(scope);
+
+        match rest {
+            Some(rest) if rest.is_empty() && declared == Some(arity) && !actual.fits(&result) => {
+                self.error(
+                    &body.location,
+                    format!("{name} is declared to return {result} but its body is {actual}"),
+                );
+            }
+            None if self.definitions.get(&name) == Some(&1) => {
+                let inferred = Type::function(vec![Type::Unknown; arity], actual);
+                self.globals.insert(name, inferred);
+            }
+            _ => {}
+        }
+    }
+
+    fn block(&mut self, statements: &[Located<Statement>]) -> Type {
+        let scope = self.scopes.len();
+        for statement in statements {
+            if let Statement::Definition { name, .. } | Statement::Variable { name, .. } =
+                &statement.node
+            {
+                self.bind(name, Type::Unknown);
+            }
+        }
+
+        let mut result = Type::None;
+        for statement in statements {
+            result = match &statement.node {
+                Statement::Definition {
+                    parameter, body, ..
+                } => {
+                    let inner = self.scopes.len();
+                    self.bind(parameter, Type::Unknown);
+                    self.expression(body);
+                    self.scopes.truncate(inner);
+                    Type::None
+                }
+                Statement::Variable { body, .. } => {
+                    self.expression(body);
+                    Type::None
+                }
+                Statement::Expression { expression } => self.expression(expression),
+                _ => Type::None,
+            };
+        }
+        self.scopes.truncate(scope);
+        result
+    }
+
+    fn bind(&mut self, parameter: &Located<Token>, ty: Type) {
+        if let Some(name) = parameter.node.get_identifier_name() {
+            self.scopes.push((name, ty));
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Type {
+        self.scopes
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, ty)| ty)
+            .or_else(|| self.globals.get(name))
+            .cloned()
+            .unwrap_or(Type::Unknown)
+    }
+
+    fn expect(&mut self, expression: &Located<Expression>, expected: &Type) -> Type {
+        if is_hole(expression) {
+            self.hole(&expression.location, expected);
+            expected.clone()
+        } else {
+            self.expression(expression)
+        }
+    }
+
+    fn hole(&mut self, location: &Rc<Location>, expected: &Type) {
+        let mut candidates: Vec<(&str, &Type)> = vec![];
+        for (name, ty) in self.scopes.iter().rev() {
+            if !candidates.iter().any(|(bound, _)| bound == name) {
+                candidates.push((name, ty));
+            }
+        }
+        let mut globals: Vec<_> = self
+            .globals
+            .iter()
+            .filter(|(name, _)| !candidates.iter().any(|(bound, _)| bound == name))
+            .map(|(name, ty)| (name.as_str(), ty))
+            .collect();
+        globals.sort_unstable_by_key(|(name, _)| *name);
+        candidates.extend(globals);
+
+        let candidates: Vec<_> = candidates
+            .into_iter()
+            .filter(|(_, ty)| ty.fits(expected))
+            .map(|(name, ty)| format!("{name}: {ty}"))
+            .collect();
+        let wanted = if expected.is_known() {
+            format!("This hole needs a value of type {expected}")
+        } else {
+            "This hole can be a value of any type".to_string()
+        };
+        let message = if candidates.is_empty() {
+            format!("{wanted}, nothing in scope fits")
+        } else {
+            format!("{wanted}, in scope: {}", candidates.join(", "))
+        };
+        self.errors
+            .push(Error::with_help(ErrorKind::Hole, location.clone(), message));
+    }
+
+    fn error(&mut self, location: &Rc<Location>, message: String) {
+        self.errors.push(Error::with_help(
+            ErrorKind::MismatchedTypes,
+            location.clone(),
+            message,
+        ));
+    }
+
+    fn expression(&mut self, expression: &Located<Expression>) -> Type {
+        match &expression.node {
+            _ if is_hole(expression) => self.expect(expression, &Type::Unknown),
+            Expression::Literal { token } => match token.node.value {
+                TokenValue::Boolean(_) => Type::Boolean,
+                TokenValue::Float(_) => Type::Float,
+                TokenValue::Integer(_) => Type::Integer,
+                TokenValue::String(_) => Type::String,
+                TokenValue::None => Type::None,
+                _ => Type::Unknown,
+            },
+            Expression::Identifier { token } => token
+                .node
+                .get_identifier_name()
+                .map_or(Type::Unknown, |name| self.lookup(&name)),
+            Expression::Unary {
+                operator,
+                expression,
+            } => {
+                let operand = self.expression(expression);
+                self.unary(operator, operand)
+            }
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                let (left, right) = if operator.node.kind == TokenKind::Colon {
+                    (self.expression(left), self.expect(right, &Type::List))
+                } else if is_hole(left) {
+                    let right = self.expression(right);
+                    (self.expect(left, &right), right)
+                } else {
+                    let left = self.expression(left);
+                    (left.clone(), self.expect(right, &left))
+                };
+                self.binary(operator, &left, &right)
+            }
+            Expression::Call { function, argument } => self.call(function, argument),
+            Expression::If {
+                branches,
+                otherwise,
+            } => self.conditional(branches, otherwise),
+            Expression::Lambda { parameter, body } => {
+                let scope = self.scopes.len();
+                self.bind(parameter, Type::Unknown);
+                let result = self.expression(body);
+                self.scopes.truncate(scope);
+                Type::function(vec![Type::Unknown], result)
+            }
+            Expression::Let { name, value, body } => {
+                let value = self.expression(value);
+                let scope = self.scopes.len();
+                self.bind(name, value);
+                let result = self.expression(body);
+                self.scopes.truncate(scope);
+                result
+            }
+            Expression::Block { statements } => self.block(statements),
+            Expression::List { elements } => {
+                for element in elements {
+                    self.expression(element);
+                }
+                Type::List
+            }
+            Expression::Record { fields } => {
+                for (_, value) in fields {
+                    self.expression(value);
+                }
+                Type::Record
+            }
+            Expression::Match { arguments, clauses } => self.equations(arguments, clauses),
+            Expression::Field { record, field } => {
+                let record = self.expression(record);
+                if record.is_known() && record != Type::Record {
+                    let field = field.node.get_identifier_name().unwrap_or_default();
+                    self.error(
+                        &expression.location,
+                        format!("Cannot access field {field} of {record}, it is not a record"),
+                    );
+                }
+                Type::Unknown
+            }
+        }
+    }
+
+    fn equations(
+        &mut self,
+        arguments: &[Located<Token>],
+        clauses: &[(Vec<Located<Token>>, Located<Expression>)],
+    ) -> Type {
+        let types: Vec<_> = arguments
+            .iter()
+            .map(|argument| {
+                argument
+                    .node
+                    .get_identifier_name()
+                    .map_or(Type::Unknown, |name| self.lookup(&name))
+            })
+            .collect();
+
+        let mut result = Type::None;
+        for (patterns, body) in clauses {
+            let scope = self.scopes.len();
+            for (pattern, ty) in patterns.iter().zip(&types) {
+                self.bind(pattern, ty.clone());
+            }
+            let ty = self.expression(body);
+            self.scopes.truncate(scope);
+            result = result.clone().unify(ty.clone()).unwrap_or_else(|| {
+                self.error(
+                    &body.location,
+                    format!(
+                        "The equations of this function have different types, {result} and {ty}"
+                    ),
+                );
+                Type::Unknown
+            });
+        }
+        result
+    }
+
+    fn unary(&mut self, operator: &Located<Token>, operand: Type) -> Type {
+        let (expected, name) = match operator.node.kind {
+            TokenKind::Bang => (operand == Type::Boolean, "!"),
+            TokenKind::Minus => (operand.is_numeric(), "-"),
+            _ => return Type::Unknown,
+        };
+        if expected || !operand.is_known() {
+            operand
+        } else {
+            self.error(
+                &operator.location,
+                format!("Cannot apply {name} to {operand}"),
+            );
+            Type::Unknown
+        }
+    }
+
+    fn binary(&mut self, operator: &Located<Token>, left: &Type, right: &Type) -> Type {
+        let kind = operator.node.kind;
+        if kind == TokenKind::Colon {
+            if right.is_known() && *right != Type::List {
+                self.error(
+                    &operator.location,
+                    format!("Cannot prepend to {right}, the right side of : must be a List"),
+                );
+            }
+            return Type::List;
+        }
+        if !left.is_known() || !right.is_known() {
+            return if kind.is_comparison() {
+                Type::Boolean
+            } else {
+                Type::Unknown
+            };
+        }
+
+        let result = match kind {
+            TokenKind::Plus if left == right && matches!(left, Type::String | Type::List) => {
+                Some(left.clone())
+            }
+            TokenKind::Plus
+            | TokenKind::Minus
+            | TokenKind::Star
+            | TokenKind::Slash
+            | TokenKind::Percent
+            | TokenKind::StarStar
+                if left.is_numeric() && right.is_numeric() =>
+            {
+                left.clone().unify(right.clone())
+            }
+            TokenKind::Ampersand | TokenKind::Pipe | TokenKind::Caret
+                if left == right && matches!(left, Type::Integer | Type::Boolean) =>
+            {
+                Some(left.clone())
+            }
+            TokenKind::EqualEqual | TokenKind::BangEqual => {
+                left.clone().unify(right.clone()).map(|_| Type::Boolean)
+            }
+            kind if kind.is_ordering()
+                && (left.is_numeric() && right.is_numeric()
+                    || left == right
+                        && matches!(left, Type::Boolean | Type::String | Type::List)) =>
+            {
+                Some(Type::Boolean)
+            }
+            kind if kind.is_comparison()
+                || matches!(
+                    kind,
+                    TokenKind::Plus
+                        | TokenKind::Minus
+                        | TokenKind::Star
+                        | TokenKind::Slash
+                        | TokenKind::Percent
+                        | TokenKind::StarStar
+                        | TokenKind::Ampersand
+                        | TokenKind::Pipe
+                        | TokenKind::Caret
+                ) =>
+            {
+                None
+            }
+            _ => Some(Type::Unknown),
+        };
+        result.unwrap_or_else(|| {
+            self.error(
+                &operator.location,
+                format!("Cannot apply {kind:?} to {left} and {right}"),
+            );
+            Type::Unknown
+        })
+    }
+
+    fn call(&mut self, function: &Located<Expression>, argument: &Located<Expression>) -> Type {
+        let callee = self.expression(function);
+        let expected = match &callee {
+            Type::Function { parameters, .. } => parameters[0].clone(),
+            _ => Type::Unknown,
+        };
+        let actual = self.expect(argument, &expected);
+        match callee {
+            Type::Function {
+                mut parameters,
+                result,
+            } => {
+                parameters.remove(0);
+                if !actual.fits(&expected) {
+                    self.error(
+                        &argument.location,
+                        format!("Expected an argument of type {expected} but found {actual}"),
+                    );
+                }
+                Type::function(parameters, *result)
+            }
+            Type::Unknown => Type::Unknown,
+            callee => {
+                self.error(
+                    &function.location,
+                    format!("Cannot call {callee}, it is not a function"),
+                );
+                Type::Unknown
+            }
+        }
+    }
+
+    fn conditional(
+        &mut self,
+        branches: &[(Located<Expression>, Located<Expression>)],
+        otherwise: &Located<Expression>,
+    ) -> Type {
+        let mut result = Type::None;
+        for (condition, body) in branches
+            .iter()
+            .map(|(c, b)| (Some(c), b))
+            .chain([(None, otherwise)])
+        {
+            if let Some(condition) = condition {
+                let ty = self.expect(condition, &Type::Boolean);
+                if ty.is_known() && ty != Type::Boolean {
+                    self.error(
+                        &condition.location,
+                        format!("The condition of an if must be Boolean, found {ty}"),
+                    );
+                }
+            }
+            let ty = self.expression(body);
+            result = result.clone().unify(ty.clone()).unwrap_or_else(|| {
+                self.error(
+                    &body.location,
+                    format!("The branches of this if have different types, {result} and {ty}"),
+                );
+                Type::Unknown
+            });
+        }
+        result
+    }
+}
+
+fn is_hole(expression: &Located<Expression>) -> bool {
+    matches!(&expression.node, Expression::Literal { token } if token.node.kind == TokenKind::Underscore)
+}
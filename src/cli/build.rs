@@ -0,0 +1,139 @@
+use super::check::parse;
+use super::config::Config;
+use super::{USAGE, collect_sources};
+use crate::checker;
+use crate::interpreter::Interpreter;
+use crate::resolver;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const CACHE_DIRECTORY: &str = ".icypeas";
+const CACHE_FILE: &str = "build.cache";
+
+pub fn run(args: &[String], config: &Config) -> ExitCode {
+    let mut paths = vec![];
+    let mut force = false;
+    for arg in args {
+        match arg.as_str() {
+            "--force" => force = true,
+            flag if flag.starts_with('-') => {
+                eprintln!("Unknown option {flag}\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if paths.is_empty() {
+        paths = config
+            .sources
+            .clone()
+            .unwrap_or_else(|| vec![PathBuf::from(".")]);
+    }
+
+    let mut files = vec![];
+    for path in &paths {
+        if let Err(e) = collect_sources(path, &mut files) {
+            eprintln!("Error: Failed to read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let cache_path = Path::new(CACHE_DIRECTORY).join(CACHE_FILE);
+    let cached = if force {
+        HashMap::new()
+    } else {
+        read_cache(&cache_path)
+    };
+    let mut built = HashMap::new();
+    let mut cache_hits = 0;
+    let mut error_count = 0;
+    let mut index = 0;
+    while let Some(file) = files.get(index).cloned() {
+        index += 1;
+        let source = match fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error: Failed to read {}: {e}", file.display());
+                return ExitCode::FAILURE;
+            }
+        };
+        let (statements, mut errors) = parse(&source);
+        for import in &resolver::resolve(&statements).imports {
+            match resolver::import_path(Some(&file), import) {
+                Ok(import) if !files.contains(&import) => files.push(import),
+                Ok(_) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let hash = hash(&source);
+        if errors.is_empty() && cached.get(&file) == Some(&hash) {
+            cache_hits += 1;
+            built.insert(file, hash);
+            continue;
+        }
+        if errors.is_empty() {
+            errors = checker::check(&statements);
+        }
+        if errors.is_empty()
+            && let Err(e) = Interpreter::check_declarations(&statements)
+        {
+            errors.push(e);
+        }
+        for error in &errors {
+            eprintln!("{}: {error}", file.display());
+        }
+        if errors.is_empty() {
+            built.insert(file, hash);
+        }
+        error_count += errors.len();
+    }
+
+    if let Err(e) = write_cache(&cache_path, &built) {
+        eprintln!("Error: Failed to write {}: {e}", cache_path.display());
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "Built {} modules ({cache_hits} cached), found {error_count} errors.",
+        files.len()
+    );
+    if error_count == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn hash(source: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn read_cache(path: &Path) -> HashMap<PathBuf, u64> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (hash, file) = line.split_once(' ')?;
+            Some((PathBuf::from(file), u64::from_str_radix(hash, 16).ok()?))
+        })
+        .collect()
+}
+
+fn write_cache(path: &Path, built: &HashMap<PathBuf, u64>) -> std::io::Result<()> {
+    let mut entries: Vec<_> = built.iter().collect();
+    entries.sort();
+    let mut cache = String::new();
+    for (file, hash) in entries {
+        let _ = writeln!(cache, "{hash:016x} {}", file.display());
+    }
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    fs::write(path, cache)
+}
@@ -0,0 +1,171 @@
+use super::rename::{rename_sites, replace_at, uses_name};
+use super::usage;
+use crate::error::Result;
+use crate::lexer::Lexer;
+use crate::model::{Located, Statement};
+use crate::parser::Parser;
+use crate::resolver::{self, Resolution};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn run(args: &[String]) -> ExitCode {
+    let (entry, output) = match args {
+        [entry] => (PathBuf::from(entry), None),
+        [entry, flag, output] if flag == "-o" || flag == "--output" => {
+            (PathBuf::from(entry), Some(PathBuf::from(output)))
+        }
+        _ => return usage(),
+    };
+
+    let bundle = match bundle(&entry) {
+        Ok(bundle) => bundle,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match output {
+        Some(output) => {
+            if let Err(e) = fs::write(&output, bundle) {
+                eprintln!("Error: Failed to write {}: {e}", output.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{bundle}"),
+    }
+    ExitCode::SUCCESS
+}
+
+struct Module {
+    path: PathBuf,
+    source: String,
+    uses: Vec<usize>,
+    resolution: Resolution,
+}
+
+fn bundle(entry: &Path) -> std::result::Result<String, String> {
+    let mut modules = vec![];
+    load(entry, &mut modules, &mut vec![])?;
+
+    let main = modules.len() - 1;
+    let base = entry.parent().unwrap_or(entry);
+    let mut definers: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, module) in modules.iter().enumerate() {
+        let names: HashSet<_> = module
+            .resolution
+            .definitions
+            .iter()
+            .map(|symbol| symbol.name.as_str())
+            .collect();
+        for name in names {
+            definers.entry(name).or_default().push(i);
+        }
+    }
+
+    let mut renames: Vec<Vec<(String, String)>> = vec![vec![]; modules.len()];
+    for (name, defining) in definers.iter().filter(|(_, defining)| defining.len() > 1) {
+        let new_names: Vec<_> = defining
+            .iter()
+            .map(|&i| {
+                if i == main {
+                    (*name).to_string()
+                } else {
+                    let path = modules[i]
+                        .path
+                        .strip_prefix(base)
+                        .unwrap_or(&modules[i].path);
+                    fresh_name(name, path, &modules)
+                }
+            })
+            .collect();
+        for (i, renamed) in renames.iter_mut().enumerate() {
+            let visible = defining
+                .iter()
+                .rposition(|&definer| definer <= i)
+                .unwrap_or(defining.len() - 1);
+            if new_names[visible] != *name {
+                renamed.push(((*name).to_string(), new_names[visible].clone()));
+            }
+        }
+    }
+
+    let mut bundle = String::new();
+    for (module, renames) in modules.iter().zip(&renames) {
+        let mut source = module.source.clone();
+        for (old, new) in renames {
+            let locations = rename_sites(&module.resolution, old);
+            source = replace_at(&source, &locations, old, new)
+                .map_err(|e| format!("{}: {e}", module.path.display()))?;
+        }
+        let _ = writeln!(bundle, "-- {}", module.path.display());
+        for (row, line) in source.lines().enumerate() {
+            if !module.uses.contains(&row) {
+                let _ = writeln!(bundle, "{line}");
+            }
+        }
+        bundle.push('\n');
+    }
+    Ok(bundle)
+}
+
+fn load(
+    path: &Path,
+    modules: &mut Vec<Module>,
+    loading: &mut Vec<PathBuf>,
+) -> std::result::Result<(), String> {
+    if modules.iter().any(|module| module.path == path) || loading.iter().any(|p| p == path) {
+        return Ok(());
+    }
+    loading.push(path.to_path_buf());
+
+    let source =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let statements = parse(&source).map_err(|e| format!("{}: {e}", path.display()))?;
+    let mut uses = vec![];
+    for statement in &statements {
+        if let Statement::Use { path: import } = &statement.node {
+            let import = resolver::import_path(Some(path), import)
+                .map_err(|e| format!("{}: {e}", path.display()))?;
+            load(&import, modules, loading)?;
+            uses.push(statement.location.row);
+        }
+    }
+
+    loading.pop();
+    modules.push(Module {
+        path: path.to_path_buf(),
+        resolution: resolver::resolve(&statements),
+        source,
+        uses,
+    });
+    Ok(())
+}
+
+fn parse(source: &str) -> Result<Vec<Located<Statement>>> {
+    let tokens = Lexer::new().lex(source)?;
+    Parser::new().parse(tokens)
+}
+
+fn fresh_name(name: &str, path: &Path, modules: &[Module]) -> String {
+    let prefix: String = path
+        .with_extension("")
+        .components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .filter(|component| component.chars().all(|c| c.is_alphanumeric() || c == '_'))
+        .collect::<Vec<_>>()
+        .join("_");
+    let base = format!("{prefix}_{name}");
+    let mut candidate = base.clone();
+    let mut suffix = 1;
+    while modules
+        .iter()
+        .any(|module| uses_name(&module.resolution, &candidate))
+    {
+        suffix += 1;
+        candidate = format!("{base}{suffix}");
+    }
+    candidate
+}
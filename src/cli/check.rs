@@ -0,0 +1,115 @@
+use super::config::Config;
+use super::{USAGE, collect_sources};
+use crate::checker;
+use crate::error::Error;
+use crate::lexer::Lexer;
+use crate::model::{Located, Statement};
+use crate::parser::Parser;
+use crate::resolver::{self, Binding, Resolution};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+pub fn run(args: &[String], config: &Config) -> ExitCode {
+    let mut paths = vec![];
+    let mut syntax_only = false;
+    let mut warnings_as_errors = config.warnings_as_errors.unwrap_or(false);
+    for arg in args {
+        match arg.as_str() {
+            "--syntax-only" => syntax_only = true,
+            "--warnings-as-errors" => warnings_as_errors = true,
+            flag if flag.starts_with('-') => {
+                eprintln!("Unknown option {flag}\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from("."));
+    }
+
+    let mut files = vec![];
+    for path in &paths {
+        if let Err(e) = collect_sources(path, &mut files) {
+            eprintln!("Error: Failed to read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut error_count = 0;
+    let mut resolutions = vec![];
+    for file in &files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error: Failed to read {}: {e}", file.display());
+                return ExitCode::FAILURE;
+            }
+        };
+        let (statements, mut errors) = parse(&source);
+        let parsed = errors.is_empty();
+        if !syntax_only && parsed {
+            errors = checker::check(&statements);
+        }
+        for error in &errors {
+            eprintln!("{}: {error}", file.display());
+        }
+        error_count += errors.len();
+        if !syntax_only && parsed {
+            resolutions.push((file, resolver::resolve(&statements)));
+        }
+    }
+
+    let warning_count = report_deprecations(&resolutions);
+
+    println!(
+        "Checked {} files, found {error_count} errors and {warning_count} warnings.",
+        files.len()
+    );
+    if error_count == 0 && (warning_count == 0 || !warnings_as_errors) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+pub fn parse(source: &str) -> (Vec<Located<Statement>>, Vec<Error>) {
+    let mut lexer = Lexer::new();
+    match lexer.lex(source) {
+        Ok(tokens) => Parser::new().parse_with_errors(tokens),
+        Err(e) => (vec![], vec![e]),
+    }
+}
+
+fn report_deprecations(resolutions: &[(&PathBuf, Resolution)]) -> usize {
+    let deprecations: HashMap<_, _> = resolutions
+        .iter()
+        .flat_map(|(_, resolution)| &resolution.deprecations)
+        .collect();
+
+    let mut warning_count = 0;
+    for (file, resolution) in resolutions {
+        for reference in &resolution.references {
+            if !matches!(reference.binding, Binding::Global | Binding::Free) {
+                continue;
+            }
+            let Some(message) = deprecations.get(&reference.name) else {
+                continue;
+            };
+            eprint!(
+                "{}: Deprecated `{}` used at {}",
+                file.display(),
+                reference.name,
+                reference.location
+            );
+            match message {
+                Some(message) => eprintln!("\nHelp: {message}"),
+                None => eprintln!(),
+            }
+            warning_count += 1;
+        }
+    }
+    warning_count
+}
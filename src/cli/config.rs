@@ -0,0 +1,108 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = ".icypeas.toml";
+
+#[derive(Debug, Default)]
+pub struct Config {
+    pub color: Option<bool>,
+    pub timeout: Option<f64>,
+    pub warn_lazy_effects: Option<bool>,
+    pub verbose: Option<bool>,
+    pub strict: Option<bool>,
+    pub warnings_as_errors: Option<bool>,
+    pub sources: Option<Vec<PathBuf>>,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = env::current_dir().ok().as_deref().and_then(find) else {
+            return Ok(Self::default());
+        };
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::parse(&source)
+            .map_err(|(line, message)| format!("{}:{line}: {message}", path.display()))
+    }
+
+    fn parse(source: &str) -> Result<Self, (usize, String)> {
+        let mut config = Self::default();
+        for (i, line) in source.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err((i + 1, format!("Expected key = value, found {line}")));
+            };
+            config
+                .set(key.trim(), value.trim())
+                .map_err(|message| (i + 1, message))?;
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let flag = match key {
+            "color" => &mut self.color,
+            "warn-lazy-effects" => &mut self.warn_lazy_effects,
+            "verbose" => &mut self.verbose,
+            "strict" => &mut self.strict,
+            "warnings-as-errors" => &mut self.warnings_as_errors,
+            "sources" => {
+                self.sources = Some(strings(value).ok_or_else(|| {
+                    format!("Expected a list of strings for sources, found {value}")
+                })?);
+                return Ok(());
+            }
+            "timeout" => {
+                let seconds = value.parse().map_err(|_| {
+                    format!("Expected a number of seconds for timeout, found {value}")
+                })?;
+                self.timeout = Some(seconds);
+                return Ok(());
+            }
+            _ => return Err(format!("Unknown option {key}")),
+        };
+        *flag = Some(match value {
+            "true" => true,
+            "false" => false,
+            _ => return Err(format!("Expected true or false for {key}, found {value}")),
+        });
+        Ok(())
+    }
+}
+
+fn find(directory: &Path) -> Option<PathBuf> {
+    directory
+        .ancestors()
+        .map(|directory| directory.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, character) in line.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn strings(value: &str) -> Option<Vec<PathBuf>> {
+    let items = value.strip_prefix('[')?.strip_suffix(']')?.trim();
+    if items.is_empty() {
+        return Some(vec![]);
+    }
+    items
+        .split(',')
+        .map(|item| {
+            let item = item.trim();
+            item.strip_prefix('"')?.strip_suffix('"').map(PathBuf::from)
+        })
+        .collect()
+}
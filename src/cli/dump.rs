@@ -0,0 +1,255 @@
+use super::usage;
+use crate::lexer::Lexer;
+use crate::model::{Expression, Located, Location, Statement, Token, TokenValue};
+use crate::parser::Parser;
+use std::fmt::Write;
+use std::fs;
+use std::process::ExitCode;
+
+pub fn tokens(args: &[String]) -> ExitCode {
+    let [file] = args else {
+        return usage();
+    };
+    let source = match fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: Failed to read {file}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match Lexer::new().lex(&source) {
+        Ok(tokens) => {
+            for token in tokens {
+                let kind = format!("{:?}", token.node.kind);
+                println!(
+                    "{:<8} {kind:<16} {:?}",
+                    token.location.to_string(),
+                    token.node.value
+                );
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{file}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn ast(args: &[String]) -> ExitCode {
+    let [file] = args else {
+        return usage();
+    };
+    let source = match fs::read_to_string(file) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: Failed to read {file}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match Lexer::new()
+        .lex(&source)
+        .and_then(|tokens| Parser::new().parse(tokens))
+    {
+        Ok(statements) => {
+            let mut tree = Tree::default();
+            for statement in &statements {
+                tree.statement(statement);
+            }
+            print!("{}", tree.output);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{file}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tree {
+    output: String,
+    depth: usize,
+}
+
+impl Tree {
+    fn line(&mut self, label: &str, location: &Location) {
+        let indent = "  ".repeat(self.depth);
+        let _ = writeln!(self.output, "{indent}{label} @{location}");
+    }
+
+    fn nested(&mut self, label: &str, location: &Location, children: impl FnOnce(&mut Self)) {
+        self.line(label, location);
+        self.depth += 1;
+        children(self);
+        self.depth -= 1;
+    }
+
+    fn token(&mut self, label: &str, token: &Located<Token>) {
+        self.line(&format!("{label} {}", describe(token)), &token.location);
+    }
+
+    fn statement(&mut self, statement: &Located<Statement>) {
+        let location = &statement.location;
+        match &statement.node {
+            Statement::Attributed {
+                attributes,
+                statement,
+            } => self.nested("Attributed", location, |tree| {
+                for attribute in attributes {
+                    tree.nested("attribute", &attribute.name.location, |tree| {
+                        tree.token("name", &attribute.name);
+                        for argument in &attribute.arguments {
+                            tree.token("argument", argument);
+                        }
+                    });
+                }
+                tree.statement(statement);
+            }),
+            Statement::Declaration { name, types } => {
+                self.nested("Declaration", location, |tree| {
+                    tree.token("name", name);
+                    for ty in types {
+                        tree.token("type", ty);
+                    }
+                });
+            }
+            Statement::Data { name, constructors } => self.nested("Data", location, |tree| {
+                tree.token("name", name);
+                for constructor in constructors {
+                    tree.token("constructor", constructor);
+                }
+            }),
+            Statement::Definition {
+                name,
+                parameter,
+                body,
+            } => self.nested("Definition", location, |tree| {
+                tree.token("name", name);
+                tree.token("parameter", parameter);
+                tree.expression(body);
+            }),
+            Statement::Expression { expression } => {
+                self.nested("Expression", location, |tree| tree.expression(expression));
+            }
+            Statement::Mutual { definitions } => self.nested("Mutual", location, |tree| {
+                for definition in definitions {
+                    tree.statement(definition);
+                }
+            }),
+            Statement::Newtype { name } => {
+                self.nested("Newtype", location, |tree| tree.token("name", name));
+            }
+            Statement::Pragma { name } => {
+                self.nested("Pragma", location, |tree| tree.token("name", name));
+            }
+            Statement::Use { path } => self.nested("Use", location, |tree| {
+                for part in path {
+                    tree.token("path", part);
+                }
+            }),
+            Statement::Variable { name, body } => self.nested("Variable", location, |tree| {
+                tree.token("name", name);
+                tree.expression(body);
+            }),
+        }
+    }
+
+    fn expression(&mut self, expression: &Located<Expression>) {
+        let location = &expression.location;
+        match &expression.node {
+            Expression::Unary {
+                operator,
+                expression,
+            } => self.nested("Unary", location, |tree| {
+                tree.token("operator", operator);
+                tree.expression(expression);
+            }),
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => self.nested("Binary", location, |tree| {
+                tree.expression(left);
+                tree.token("operator", operator);
+                tree.expression(right);
+            }),
+            Expression::Call { function, argument } => self.nested("Call", location, |tree| {
+                tree.expression(function);
+                tree.expression(argument);
+            }),
+            Expression::Identifier { token } => self.token("Identifier", token),
+            Expression::If {
+                branches,
+                otherwise,
+            } => self.nested("If", location, |tree| {
+                for (condition, body) in branches {
+                    tree.expression(condition);
+                    tree.expression(body);
+                }
+                tree.expression(otherwise);
+            }),
+            Expression::Lambda { parameter, body } => self.nested("Lambda", location, |tree| {
+                tree.token("parameter", parameter);
+                tree.expression(body);
+            }),
+            Expression::Let { name, value, body } => self.nested("Let", location, |tree| {
+                tree.token("name", name);
+                tree.expression(value);
+                tree.expression(body);
+            }),
+            Expression::Block { statements } => self.nested("Block", location, |tree| {
+                for statement in statements {
+                    tree.statement(statement);
+                }
+            }),
+            Expression::List { elements } => self.nested("List", location, |tree| {
+                for element in elements {
+                    tree.expression(element);
+                }
+            }),
+            Expression::Record { fields } => self.nested("Record", location, |tree| {
+                for (name, value) in fields {
+                    tree.nested(
+                        &format!("field {}", describe(name)),
+                        &name.location,
+                        |tree| {
+                            tree.expression(value);
+                        },
+                    );
+                }
+            }),
+            Expression::Field { record, field } => self.nested("Field", location, |tree| {
+                tree.expression(record);
+                tree.token("name", field);
+            }),
+            Expression::Literal { token } => self.token("Literal", token),
+            Expression::Match { arguments, clauses } => self.nested("Match", location, |tree| {
+                for argument in arguments {
+                    tree.token("argument", argument);
+                }
+                for (patterns, body) in clauses {
+                    tree.nested("Equation", &body.location, |tree| {
+                        for pattern in patterns {
+                            tree.token("pattern", pattern);
+                        }
+                        tree.expression(body);
+                    });
+                }
+            }),
+        }
+    }
+}
+
+fn describe(token: &Located<Token>) -> String {
+    match &token.node.value {
+        TokenValue::Identifier(name) => name.clone(),
+        TokenValue::Boolean(b) => b.to_string(),
+        TokenValue::Float(f) => f.to_string(),
+        TokenValue::Integer(i) => i.to_string(),
+        TokenValue::String(s) => format!("{s:?}"),
+        _ => format!("{:?}", token.node.kind),
+    }
+}
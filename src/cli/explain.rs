@@ -0,0 +1,75 @@
+use super::usage;
+use crate::engine::Engine;
+use crate::explain::{html, print};
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+pub const STEP_LIMIT: usize = 100;
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut expression = None;
+    let mut file = None;
+    let mut output = None;
+    let mut limit = STEP_LIMIT;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--in" => match args.next() {
+                Some(path) => file = Some(PathBuf::from(path)),
+                None => return usage(),
+            },
+            "--html" => match args.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => return usage(),
+            },
+            "--steps" => match args.next().and_then(|steps| steps.parse().ok()) {
+                Some(steps) => limit = steps,
+                None => return usage(),
+            },
+            _ if expression.is_none() => expression = Some(arg),
+            _ => return usage(),
+        }
+    }
+    let Some(expression) = expression else {
+        return usage();
+    };
+
+    let source = match &file {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Error: Failed to read {}: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => String::new(),
+    };
+
+    let explanation = match Engine::new().explain(&source, file, expression, limit) {
+        Ok(explanation) => explanation,
+        Err(e) => {
+            eprintln!("<expression>: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(path) = output {
+        if let Err(e) = fs::write(&path, html::render(&explanation, limit)) {
+            eprintln!("Error: Failed to write {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    } else {
+        for (i, step) in explanation.steps.iter().enumerate() {
+            let arrow = if i == 0 { "" } else { "=> " };
+            println!("{arrow}{}", print(step));
+        }
+        if explanation.truncated {
+            println!("... stopped after {limit} steps");
+        }
+    }
+    explanation.error.map_or(ExitCode::SUCCESS, |e| {
+        eprintln!("<expression>: {e}");
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,160 @@
+use super::usage;
+use crate::error::Result;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::resolver::{self, Binding, Resolution};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut file = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().map(String::as_str) {
+                Some("dot") => {}
+                Some(format) => {
+                    eprintln!("Unsupported graph format {format}, expected dot");
+                    return ExitCode::FAILURE;
+                }
+                None => return usage(),
+            },
+            path if file.is_none() => file = Some(PathBuf::from(path)),
+            _ => return usage(),
+        }
+    }
+    let Some(file) = file else {
+        return usage();
+    };
+
+    let mut modules = vec![];
+    if let Err(e) = load_modules(&file, &mut modules) {
+        eprintln!("Error: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    print!("{}", to_dot(&modules));
+    ExitCode::SUCCESS
+}
+
+struct Module {
+    path: PathBuf,
+    imports: Vec<PathBuf>,
+    resolution: Resolution,
+}
+
+fn load_modules(path: &Path, modules: &mut Vec<Module>) -> std::result::Result<(), String> {
+    if modules.iter().any(|module| module.path == path) {
+        return Ok(());
+    }
+
+    let source =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let (resolution, imports) =
+        analyze(path, &source).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    modules.push(Module {
+        path: path.to_path_buf(),
+        imports: imports.clone(),
+        resolution,
+    });
+    for import in imports {
+        load_modules(&import, modules)?;
+    }
+    Ok(())
+}
+
+fn analyze(path: &Path, source: &str) -> Result<(Resolution, Vec<PathBuf>)> {
+    let tokens = Lexer::new().lex(source)?;
+    let statements = Parser::new().parse(tokens)?;
+    let resolution = resolver::resolve(&statements);
+
+    let imports = resolution
+        .imports
+        .iter()
+        .map(|import| resolver::import_path(Some(path), import))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((resolution, imports))
+}
+
+fn to_dot(modules: &[Module]) -> String {
+    let mut dot = String::from("digraph icypeas {\n");
+    for module in modules {
+        let path = quote(&module.path.display().to_string());
+        let _ = writeln!(dot, "    {path} [shape=box];");
+        for import in &module.imports {
+            let _ = writeln!(
+                dot,
+                "    {path} -> {} [style=dashed];",
+                quote(&import.display().to_string())
+            );
+        }
+    }
+
+    let definitions: BTreeSet<(&Path, &String)> = modules
+        .iter()
+        .flat_map(|module| {
+            module
+                .resolution
+                .definitions
+                .iter()
+                .map(|symbol| (module.path.as_path(), &symbol.name))
+        })
+        .collect();
+    for (path, name) in &definitions {
+        let _ = writeln!(dot, "    {} [label={}];", node(path, name), quote(name));
+    }
+
+    let calls: BTreeSet<(String, String)> = modules
+        .iter()
+        .flat_map(|module| {
+            module
+                .resolution
+                .references
+                .iter()
+                .map(move |reference| (module, reference))
+        })
+        .filter(|(_, reference)| matches!(reference.binding, Binding::Global | Binding::Free))
+        .filter_map(|(module, reference)| {
+            let caller = reference.scope.as_ref()?;
+            let definer = defining_module(modules, module, &reference.name)?;
+            Some((node(&module.path, caller), node(definer, &reference.name)))
+        })
+        .collect();
+    for (caller, callee) in calls {
+        let _ = writeln!(dot, "    {caller} -> {callee};");
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn defining_module<'a>(modules: &'a [Module], module: &'a Module, name: &str) -> Option<&'a Path> {
+    let mut pending = vec![module];
+    let mut index = 0;
+    while let Some(&module) = pending.get(index) {
+        if module.resolution.defines(name) {
+            return Some(&module.path);
+        }
+        for import in &module.imports {
+            if let Some(imported) = modules.iter().find(|module| module.path == *import)
+                && !pending.iter().any(|module| module.path == *import)
+            {
+                pending.push(imported);
+            }
+        }
+        index += 1;
+    }
+    None
+}
+
+fn node(path: &Path, name: &str) -> String {
+    quote(&format!("{}::{name}", path.display()))
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,329 @@
+mod build;
+mod bundle;
+mod check;
+mod config;
+mod dump;
+mod explain;
+mod graph;
+mod rename;
+mod symbols;
+mod test;
+
+use crate::engine::{Engine, Phases};
+use crate::error::{Error, ErrorKind};
+use crate::interpreter::Evaluation;
+use config::Config;
+use std::fs::{self, read_dir};
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str = "Usage:
+    icypeas                                     Run the test suite, or a script piped to stdin
+    icypeas test [pattern] [--format <format>]  Run the tests whose path contains pattern,
+                                                reporting as pretty, tap, junit or json
+    icypeas test <paths...>                     Run the given files and their @test definitions
+    icypeas test --doc [paths...]               Run the ``` examples in --- doc comments
+    icypeas <file> [args...]                    Run a script, passing args to args.parse
+    icypeas - [args...]                         Run a script read from stdin
+    icypeas --time <file> [args...]             Run a script and report time spent per phase
+    icypeas -v, --verbose <command...>          Report each phase with its time and item count
+    icypeas -e <expression>                     Evaluate an expression and print its value
+    icypeas explain <expression> [--in <file>]  Print each reduction step of an expression,
+                                                stopping after --steps <n> steps
+    icypeas explain <expression> --html <file>  Write the steps as an interactive HTML page
+    icypeas check [--syntax-only] [paths...]    Check files for errors and deprecated uses,
+                                                failing on warnings with --warnings-as-errors
+    icypeas --check [paths...]                  Alias for check
+    icypeas build [--force] [paths...]          Check files and their imports without running them,
+                                                skipping files unchanged since the last build
+    icypeas bundle <file> [-o <output>]         Inline a script's imports into one file
+    icypeas --dump-tokens <file>                Print the tokens of a file
+    icypeas --dump-ast <file>                   Print the syntax tree of a file
+    icypeas graph <file> [--format dot]         Print the import and call graph
+    icypeas rename <old> <new> [--in <dir>]     Rename a definition and its references
+    icypeas symbols <file>                      List the top-level symbols of a file
+    icypeas references <name> [--in <dir>]      Find a definition and its references
+    icypeas --no-color <command...>             Disable style colors in scripts
+    icypeas --color <command...>                Enable style colors in scripts
+    icypeas --strict <command...>               Evaluate every file as if it started with #strict
+    icypeas --timeout <seconds> <command...>    Cancel scripts that run longer than seconds
+    icypeas --warn-lazy-effects <command...>    Warn when forcing a lazy value runs an effect
+
+Defaults for color, timeout, verbose, strict, warn-lazy-effects, warnings-as-errors and
+the sources to build are read from the nearest .icypeas.toml as key = value lines, flags
+override them.";
+
+#[derive(Clone, Copy)]
+struct RunOptions {
+    color: bool,
+    timeout: Option<Duration>,
+    warn_lazy_effects: bool,
+    verbose: bool,
+    evaluation: Evaluation,
+}
+
+impl RunOptions {
+    fn parse<'a>(mut args: &'a [String], config: &Config) -> Option<(Self, &'a [String])> {
+        let mut options = Self {
+            color: config.color.unwrap_or(true),
+            timeout: match config.timeout {
+                Some(seconds) => Some(Duration::try_from_secs_f64(seconds).ok()?),
+                None => None,
+            },
+            warn_lazy_effects: config.warn_lazy_effects.unwrap_or(false),
+            verbose: config.verbose.unwrap_or(false),
+            evaluation: if config.strict == Some(true) {
+                Evaluation::Strict
+            } else {
+                Evaluation::Lazy
+            },
+        };
+        loop {
+            match args {
+                [flag, rest @ ..] if flag == "--no-color" || flag == "--color" => {
+                    options.color = flag == "--color";
+                    args = rest;
+                }
+                [flag, rest @ ..] if flag == "--strict" => {
+                    options.evaluation = Evaluation::Strict;
+                    args = rest;
+                }
+                [flag, rest @ ..] if flag == "--warn-lazy-effects" => {
+                    options.warn_lazy_effects = true;
+                    args = rest;
+                }
+                [flag, rest @ ..] if flag == "-v" || flag == "--verbose" => {
+                    options.verbose = true;
+                    args = rest;
+                }
+                [flag, seconds, rest @ ..] if flag == "--timeout" => {
+                    options.timeout =
+                        Some(Duration::try_from_secs_f64(seconds.parse().ok()?).ok()?);
+                    args = rest;
+                }
+                _ => return Some((options, args)),
+            }
+        }
+    }
+
+    fn engine(self, engine: Engine) -> Engine {
+        if let Some(timeout) = self.timeout {
+            let cancel = engine.cancel_handle();
+            thread::spawn(move || {
+                thread::sleep(timeout);
+                cancel.cancel();
+            });
+        }
+        engine
+            .with_color(self.color)
+            .with_lazy_effect_warnings(self.warn_lazy_effects)
+            .with_evaluation(self.evaluation)
+    }
+}
+
+pub fn run(args: &[String]) -> ExitCode {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some((options, args)) = RunOptions::parse(args, &config) else {
+        return usage();
+    };
+
+    match args.first().map(String::as_str) {
+        None if !io::stdin().is_terminal() => match read_stdin() {
+            Ok(source) if source.trim().is_empty() => test::run(&[]),
+            Ok(source) => run_source(&source, None, &[], options),
+            Err(code) => code,
+        },
+        None => test::run(&[]),
+        Some("test") => test::run(&args[1..]),
+        Some("-") => run_stdin(&args[1..], options),
+        Some("-e") => match &args[1..] {
+            [expression] => run_expression(expression, options),
+            _ => usage(),
+        },
+        Some("check" | "--check") => check::run(&args[1..], &config),
+        Some("build") => build::run(&args[1..], &config),
+        Some("bundle") => bundle::run(&args[1..]),
+        Some("explain" | "--explain") => explain::run(&args[1..]),
+        Some("--dump-tokens") => dump::tokens(&args[1..]),
+        Some("--dump-ast") => dump::ast(&args[1..]),
+        Some("--time") => match &args[1..] {
+            [file, arguments @ ..] => run_timed(Path::new(file), arguments, options),
+            _ => usage(),
+        },
+        Some("graph") => graph::run(&args[1..]),
+        Some("rename") => rename::run(&args[1..]),
+        Some("symbols") => symbols::symbols(&args[1..]),
+        Some("references") => symbols::references(&args[1..]),
+        Some(flag) if flag.starts_with('-') => usage(),
+        Some(file) => run_file(Path::new(file), &args[1..], options),
+    }
+}
+
+fn run_stdin(arguments: &[String], options: RunOptions) -> ExitCode {
+    match read_stdin() {
+        Ok(source) => run_source(&source, None, arguments, options),
+        Err(code) => code,
+    }
+}
+
+fn read_stdin() -> Result<String, ExitCode> {
+    let mut source = String::new();
+    io::stdin().read_to_string(&mut source).map_err(|e| {
+        eprintln!("Error: Failed to read stdin: {e}");
+        ExitCode::FAILURE
+    })?;
+    Ok(source)
+}
+
+fn run_expression(expression: &str, options: RunOptions) -> ExitCode {
+    let engine = RunOptions {
+        timeout: None,
+        ..options
+    }
+    .engine(Engine::new());
+    let result = options.timeout.map_or_else(
+        || engine.eval(expression),
+        |timeout| engine.eval_with_deadline(expression, timeout),
+    );
+    match result {
+        Ok(value) => {
+            println!("{value}");
+            ExitCode::SUCCESS
+        }
+        Err(Error {
+            kind: ErrorKind::Exit(code),
+            ..
+        }) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("<expression>: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_file(path: &Path, arguments: &[String], options: RunOptions) -> ExitCode {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: Failed to read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    run_source(&source, Some(path.to_path_buf()), arguments, options)
+}
+
+fn run_timed(path: &Path, arguments: &[String], options: RunOptions) -> ExitCode {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("Error: Failed to read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let name = path.display().to_string();
+    let (result, timings) = options
+        .engine(Engine::with_arguments(name.clone(), arguments.to_vec()))
+        .run_timed(&source, Some(path.to_path_buf()));
+    eprintln!(
+        "{:<32} {:>12} {:>12} {:>12}",
+        "file", "lex", "parse", "interpret"
+    );
+    for timing in timings.iter().rev() {
+        let file = timing
+            .file
+            .as_ref()
+            .map_or_else(|| "<stdin>".to_string(), |file| file.display().to_string());
+        eprintln!(
+            "{file:<32} {:>12?} {:>12?} {:>12?}",
+            timing.times.lex, timing.times.parse, timing.interpret
+        );
+    }
+    exit_code(&name, result)
+}
+
+fn run_source(
+    source: &str,
+    file: Option<PathBuf>,
+    arguments: &[String],
+    options: RunOptions,
+) -> ExitCode {
+    let name = file
+        .as_ref()
+        .map_or_else(|| "<stdin>".to_string(), |file| file.display().to_string());
+
+    let engine = options.engine(Engine::with_arguments(name.clone(), arguments.to_vec()));
+    let result = if options.verbose {
+        let (result, phases) = engine.run_phased(source, file);
+        report_phases(&phases);
+        result
+    } else {
+        engine.run(source, file)
+    };
+    exit_code(&name, result)
+}
+
+fn exit_code(name: &str, result: crate::error::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Error {
+            kind: ErrorKind::Exit(code),
+            ..
+        }) => ExitCode::from(code),
+        Err(
+            e @ Error {
+                kind: ErrorKind::Interrupted,
+                ..
+            },
+        ) => {
+            eprintln!("{name}: {e}");
+            ExitCode::from(130)
+        }
+        Err(e) => {
+            eprintln!("{name}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn report_phases(phases: &Phases) {
+    for phase in phases.iter() {
+        eprintln!(
+            "{:<8} {:>12?} {:>8} {}",
+            phase.name, phase.duration, phase.items, phase.unit
+        );
+    }
+    eprintln!("{:<8} {:>12?}", "total", phases.total());
+}
+
+fn usage() -> ExitCode {
+    eprintln!("{USAGE}");
+    ExitCode::FAILURE
+}
+
+pub fn collect_sources(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() {
+        let mut entries = read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() || entry.extension().is_some_and(|e| e == "icy") {
+                collect_sources(&entry, files)?;
+            }
+        }
+    } else {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
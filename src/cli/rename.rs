@@ -0,0 +1,217 @@
+use super::{collect_sources, usage};
+use crate::error::Result;
+use crate::interpreter::Environment;
+use crate::lexer::Lexer;
+use crate::model::{Location, TokenKind};
+use crate::parser::Parser;
+use crate::resolver::{self, Binding, Resolution};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+
+pub fn run(args: &[String]) -> ExitCode {
+    let (old, new, directory) = match args {
+        [old, new] => (old, new, PathBuf::from(".")),
+        [old, new, flag, directory] if flag == "--in" => (old, new, PathBuf::from(directory)),
+        _ => return usage(),
+    };
+
+    match rename(old, new, &directory) {
+        Ok((occurrences, files)) => {
+            println!("Renamed {occurrences} occurrences of {old} to {new} in {files} files.");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+struct File {
+    path: PathBuf,
+    module: PathBuf,
+    source: String,
+    resolution: Resolution,
+    imports: Vec<PathBuf>,
+}
+
+fn rename(old: &str, new: &str, directory: &Path) -> std::result::Result<(usize, usize), String> {
+    if !is_identifier(new) {
+        return Err(format!("{new} is not a valid identifier"));
+    }
+
+    let mut paths = vec![];
+    collect_sources(directory, &mut paths)
+        .map_err(|e| format!("Failed to read {}: {e}", directory.display()))?;
+
+    let mut files = vec![];
+    for path in paths {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let resolution = analyze(&source).map_err(|e| format!("{}: {e}", path.display()))?;
+        let imports = resolution
+            .imports
+            .iter()
+            .map(|import| resolver::import_path(Some(&path), import).map(|path| canonical(&path)))
+            .collect::<Result<_>>()
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        files.push(File {
+            module: canonical(&path),
+            path,
+            source,
+            resolution,
+            imports,
+        });
+    }
+
+    let definers: Vec<_> = files
+        .iter()
+        .filter(|file| file.resolution.defines(old))
+        .collect();
+    let files = match definers.as_slice() {
+        [] => return Err(format!("{old} is not defined in {}", directory.display())),
+        [definer] => scope(&files, &definer.module),
+        definers => {
+            let paths: Vec<_> = definers
+                .iter()
+                .map(|file| file.path.display().to_string())
+                .collect();
+            return Err(format!(
+                "{old} is defined in {}, narrow the search with --in",
+                paths.join(", ")
+            ));
+        }
+    };
+    if let Some(file) = files.iter().find(|file| uses_name(&file.resolution, new)) {
+        return Err(format!(
+            "{new} is already used in {}, refusing to rename",
+            file.path.display()
+        ));
+    }
+    let builtins = Environment::new();
+    builtins.borrow_mut().add_builtins();
+    if builtins.borrow().get(new).is_some() {
+        return Err(format!("{new} is a builtin, refusing to rename"));
+    }
+
+    let mut occurrences = 0;
+    let mut renamed = vec![];
+    for file in files {
+        let locations = rename_sites(&file.resolution, old);
+        if locations.is_empty() {
+            continue;
+        }
+
+        let source = replace_at(&file.source, &locations, old, new)
+            .map_err(|e| format!("{}: {e}", file.path.display()))?;
+        occurrences += locations.len();
+        renamed.push((&file.path, source));
+    }
+
+    for (path, source) in &renamed {
+        fs::write(path, source).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok((occurrences, renamed.len()))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn scope<'a>(files: &'a [File], module: &Path) -> Vec<&'a File> {
+    let mut modules = vec![module.to_path_buf()];
+    let mut index = 0;
+    while let Some(module) = modules.get(index).cloned() {
+        index += 1;
+        for file in files {
+            if file.imports.contains(&module) && !modules.contains(&file.module) {
+                modules.push(file.module.clone());
+            }
+        }
+    }
+    files
+        .iter()
+        .filter(|file| modules.contains(&file.module))
+        .collect()
+}
+
+fn analyze(source: &str) -> Result<Resolution> {
+    let tokens = Lexer::new().lex(source)?;
+    let statements = Parser::new().parse(tokens)?;
+    Ok(resolver::resolve(&statements))
+}
+
+fn is_identifier(name: &str) -> bool {
+    Lexer::new().lex(name).is_ok_and(
+        |tokens| matches!(tokens.as_slice(), [token] if token.node.kind == TokenKind::Identifier),
+    )
+}
+
+pub fn uses_name(resolution: &Resolution, name: &str) -> bool {
+    resolution
+        .definitions
+        .iter()
+        .chain(&resolution.declarations)
+        .chain(&resolution.parameters)
+        .any(|symbol| symbol.name == name)
+        || resolution
+            .references
+            .iter()
+            .any(|reference| reference.name == name)
+}
+
+pub fn rename_sites(resolution: &Resolution, name: &str) -> Vec<Rc<Location>> {
+    resolution
+        .definitions
+        .iter()
+        .chain(&resolution.declarations)
+        .filter(|symbol| symbol.name == name)
+        .map(|symbol| symbol.location.clone())
+        .chain(
+            resolution
+                .references
+                .iter()
+                .filter(|reference| {
+                    reference.name == name
+                        && matches!(reference.binding, Binding::Global | Binding::Free)
+                })
+                .map(|reference| reference.location.clone()),
+        )
+        .collect()
+}
+
+pub fn replace_at(
+    source: &str,
+    locations: &[Rc<Location>],
+    old: &str,
+    new: &str,
+) -> std::result::Result<String, String> {
+    let mut lines: Vec<Vec<char>> = source.split('\n').map(|l| l.chars().collect()).collect();
+    let old: Vec<char> = old.chars().collect();
+
+    let mut locations = locations.to_vec();
+    locations.sort_by_key(|location| std::cmp::Reverse((location.row, location.column)));
+    locations.dedup();
+
+    for location in locations {
+        let line = lines
+            .get_mut(location.row)
+            .ok_or_else(|| format!("No line at {location}"))?;
+        let end = location.column + old.len();
+        if line.get(location.column..end) != Some(old.as_slice()) {
+            return Err(format!(
+                "Expected {} at {location}",
+                old.iter().collect::<String>()
+            ));
+        }
+        line.splice(location.column..end, new.chars());
+    }
+
+    Ok(lines
+        .iter()
+        .map(|line| line.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
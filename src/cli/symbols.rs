@@ -0,0 +1,97 @@
+use super::{collect_sources, usage};
+use crate::error::Result;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::resolver::{self, Binding, Resolution};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub fn symbols(args: &[String]) -> ExitCode {
+    let [file] = args else {
+        return usage();
+    };
+    let path = Path::new(file);
+
+    match analyze(path) {
+        Ok(resolution) => {
+            let mut symbols: Vec<_> = resolution
+                .declarations
+                .iter()
+                .chain(&resolution.definitions)
+                .collect();
+            symbols.sort_by_key(|symbol| (symbol.location.row, symbol.location.column));
+            for symbol in symbols {
+                println!(
+                    "{}:{} {:?} {}",
+                    path.display(),
+                    symbol.location,
+                    symbol.kind,
+                    symbol.name
+                );
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+pub fn references(args: &[String]) -> ExitCode {
+    let (name, directory) = match args {
+        [name] => (name, PathBuf::from(".")),
+        [name, flag, directory] if flag == "--in" => (name, PathBuf::from(directory)),
+        _ => return usage(),
+    };
+
+    let mut files = vec![];
+    if let Err(e) = collect_sources(&directory, &mut files) {
+        eprintln!("Error: Failed to read {}: {e}", directory.display());
+        return ExitCode::FAILURE;
+    }
+
+    for file in files {
+        let resolution = match analyze(&file) {
+            Ok(resolution) => resolution,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let definitions = resolution
+            .definitions
+            .iter()
+            .filter(|symbol| &symbol.name == name)
+            .map(|symbol| (&symbol.location, "definition"));
+        let references = resolution
+            .references
+            .iter()
+            .filter(|reference| {
+                &reference.name == name
+                    && matches!(reference.binding, Binding::Global | Binding::Free)
+            })
+            .map(|reference| (&reference.location, "reference"));
+
+        let mut sites: Vec<_> = definitions.chain(references).collect();
+        sites.sort_by_key(|(location, _)| (location.row, location.column));
+        for (location, kind) in sites {
+            println!("{}:{location} {kind}", file.display());
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn analyze(path: &Path) -> std::result::Result<Resolution, String> {
+    let source =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    resolve(&source).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn resolve(source: &str) -> Result<Resolution> {
+    let tokens = Lexer::new().lex(source)?;
+    let statements = Parser::new().parse(tokens)?;
+    Ok(resolver::resolve(&statements))
+}
@@ -0,0 +1,468 @@
+use super::explain::STEP_LIMIT;
+use super::{USAGE, collect_sources};
+use crate::engine::Engine;
+use crate::error::Error;
+use crate::explain::print;
+use std::fmt::Write;
+use std::fs::{self, read_dir};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+const SLOWEST_TESTS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Pretty,
+    Tap,
+    Junit,
+    Json,
+}
+
+struct TestResult {
+    name: String,
+    outcome: Result<(), String>,
+    duration: Duration,
+}
+
+pub fn run(args: &[String]) -> ExitCode {
+    let mut filter = None;
+    let mut sources = vec![];
+    let mut format = Format::Pretty;
+    let mut doc = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = match args.next().map(String::as_str) {
+                    Some("tap") => Format::Tap,
+                    Some("junit") => Format::Junit,
+                    Some("json") => Format::Json,
+                    Some("pretty") => Format::Pretty,
+                    other => {
+                        eprintln!("Unknown test format {}\n{USAGE}", other.unwrap_or_default());
+                        return ExitCode::FAILURE;
+                    }
+                };
+            }
+            "--doc" => doc = true,
+            flag if flag.starts_with('-') => {
+                eprintln!("Unknown option {flag}\n{USAGE}");
+                return ExitCode::FAILURE;
+            }
+            path if Path::new(path).exists() => sources.push(PathBuf::from(path)),
+            pattern => filter = Some(pattern),
+        }
+    }
+
+    let paths = if sources.is_empty() {
+        test_paths(filter)
+    } else {
+        let mut paths = vec![];
+        sources
+            .iter()
+            .try_for_each(|source| collect_sources(source, &mut paths))
+            .map(|()| paths)
+    };
+    let mut paths = match paths {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("Error: Failed to read the tests: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    paths.sort();
+
+    let engine = Engine::new();
+    let mut results = Vec::new();
+
+    for path in paths {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Failed to load test {}: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let first = results.len();
+        if doc {
+            run_examples(&engine, &path, &content, &mut results);
+        } else {
+            let output = run_test(
+                &engine,
+                &path,
+                &content,
+                format == Format::Pretty,
+                &mut results,
+            );
+            if format == Format::Pretty {
+                print!("{output}");
+            }
+        }
+        if format == Format::Pretty {
+            results[first..].iter().for_each(print_pretty);
+        }
+    }
+
+    match format {
+        Format::Pretty => report_pretty(&engine, &results),
+        Format::Tap => print!("{}", tap(&results)),
+        Format::Junit => print!("{}", junit(&results)),
+        Format::Json => println!("{}", json(&results)),
+    }
+
+    if results.iter().any(|result| result.outcome.is_err()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn test_paths(filter: Option<&str>) -> std::io::Result<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for entry in read_dir("tests")? {
+        let path = entry?.path();
+        let selected = filter.is_none_or(|filter| path.to_string_lossy().contains(filter));
+        if selected && path.is_file() && path.extension().is_some_and(|e| e == "icy") {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+fn run_test(
+    engine: &Engine,
+    path: &Path,
+    content: &str,
+    color: bool,
+    results: &mut Vec<TestResult>,
+) -> String {
+    let start = Instant::now();
+    let mut run = engine.run_test(content, Some(path.to_path_buf()), TEST_TIMEOUT);
+    let duration = start.elapsed();
+    if let Some(expected) = expected_error(content)
+        && run.result.is_ok()
+        && let Some(case) = run.tests.iter_mut().find(|case| {
+            case.result
+                .as_ref()
+                .is_err_and(|e| matches_error(e, expected))
+        })
+    {
+        run.result = std::mem::replace(&mut case.result, Ok(()));
+    }
+    if let Some(expression) = explained(content)
+        && run.result.is_ok()
+    {
+        match explain(engine, path, content, expression) {
+            Ok(steps) => run.output.push_str(&steps),
+            Err(e) => run.result = Err(e),
+        }
+    }
+    let (outcome, output) = match check_run(path, content, run.result, run.output, color) {
+        Ok(output) => (Ok(()), output),
+        Err(e) => (Err(e), String::new()),
+    };
+
+    results.push(TestResult {
+        name: path.display().to_string(),
+        outcome,
+        duration,
+    });
+    results.extend(run.tests.into_iter().map(|case| TestResult {
+        name: format!("{}::{}", path.display(), case.name),
+        outcome: case.result.map_err(|e| format!("with error: {e}")),
+        duration: case.duration,
+    }));
+    output
+}
+
+fn run_examples(engine: &Engine, path: &Path, content: &str, results: &mut Vec<TestResult>) {
+    let examples = doc_examples(content);
+    if examples.is_empty() {
+        return;
+    }
+
+    let start = Instant::now();
+    let examples = examples
+        .into_iter()
+        .map(|(line, code)| (format!("{}:{line}", path.display()), code))
+        .collect();
+    match engine.run_examples(content, Some(path.to_path_buf()), examples, TEST_TIMEOUT) {
+        Ok(cases) => results.extend(cases.into_iter().map(|case| TestResult {
+            name: case.name,
+            outcome: case.result.map_err(|e| format!("with error: {e}")),
+            duration: case.duration,
+        })),
+        Err(e) => results.push(TestResult {
+            name: path.display().to_string(),
+            outcome: Err(format!("with error: {e}")),
+            duration: start.elapsed(),
+        }),
+    }
+}
+
+fn doc_examples(content: &str) -> Vec<(usize, String)> {
+    let mut examples = vec![];
+    let mut current: Option<(usize, String)> = None;
+    for (i, line) in content.lines().enumerate() {
+        let Some(doc) = line.trim_start().strip_prefix("---") else {
+            current = None;
+            continue;
+        };
+        let doc = doc.strip_prefix(' ').unwrap_or(doc);
+        if doc.trim_start().starts_with("```") {
+            match current.take() {
+                Some(example) => examples.push(example),
+                None => current = Some((i + 2, String::new())),
+            }
+        } else if let Some((_, code)) = &mut current {
+            code.push_str(doc);
+            code.push('\n');
+        }
+    }
+    examples
+}
+
+fn check_run(
+    path: &Path,
+    content: &str,
+    result: crate::error::Result<()>,
+    output: String,
+    color: bool,
+) -> Result<String, String> {
+    if let Some(expected) = expected_error(content) {
+        return match result {
+            Ok(()) => Err(format!("to raise {expected}")),
+            Err(e) if matches_error(&e, expected) => {
+                compare(&path.with_extension("stderr"), &format!("{e}\n"), color)
+            }
+            Err(e) => Err(format!("to raise {expected}, got: {e}")),
+        };
+    }
+    result.map_err(|e| format!("with error: {e}"))?;
+
+    let expected_path = path.with_extension("expected");
+    if !expected_path.exists() {
+        return Ok(output);
+    }
+    compare(&expected_path, &output, color)
+}
+
+fn compare(expected_path: &Path, output: &str, color: bool) -> Result<String, String> {
+    if !expected_path.exists() {
+        return Ok(String::new());
+    }
+
+    let expected = fs::read_to_string(expected_path)
+        .map_err(|e| format!("to load {}: {e}", expected_path.display()))?;
+    if output == expected {
+        Ok(String::new())
+    } else {
+        Err(format!(
+            "with unexpected output:\n{}",
+            diff(&expected, output, color)
+        ))
+    }
+}
+
+fn explained(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("-- explain:"))
+        .map(str::trim)
+}
+
+fn explain(engine: &Engine, path: &Path, content: &str, expression: &str) -> Result<String, Error> {
+    let explanation = engine.explain(content, Some(path.to_path_buf()), expression, STEP_LIMIT)?;
+    let mut steps = String::new();
+    for (i, step) in explanation.steps.iter().enumerate() {
+        let arrow = if i == 0 { "" } else { "=> " };
+        let _ = writeln!(steps, "{arrow}{}", print(step));
+    }
+    explanation.error.map_or(Ok(steps), Err)
+}
+
+fn expected_error(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("-- error:"))
+        .map(str::trim)
+}
+
+fn matches_error(error: &Error, expected: &str) -> bool {
+    let (kind, location) = match expected.split_once(" at ") {
+        Some((kind, location)) => (kind, Some(location)),
+        None => (expected, None),
+    };
+    format!("{:?}", error.kind) == kind
+        && location.is_none_or(|location| {
+            error
+                .location
+                .as_ref()
+                .is_some_and(|l| l.to_string() == location)
+        })
+}
+
+fn diff(expected: &str, actual: &str, color: bool) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+    let mut result = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e == a => {
+                let _ = writeln!(result, "  {e}");
+            }
+            (e, a) => {
+                if let Some(e) = e {
+                    let _ = writeln!(result, "{}", paint(&format!("- {e}"), "31", color));
+                }
+                if let Some(a) = a {
+                    let _ = writeln!(result, "{}", paint(&format!("+ {a}"), "32", color));
+                }
+            }
+        }
+    }
+    result
+}
+
+fn paint(line: &str, code: &str, color: bool) -> String {
+    if color {
+        format!("\x1b[{code}m{line}\x1b[0m")
+    } else {
+        line.to_string()
+    }
+}
+
+fn print_pretty(result: &TestResult) {
+    match &result.outcome {
+        Ok(()) => println!(
+            "\x1b[32mSUCCESS\x1b[0m {} completed successfully in {}.",
+            result.name,
+            milliseconds(result.duration)
+        ),
+        Err(e) => eprintln!(
+            "\x1b[31mFAILED\x1b[0m {} failed after {} {e}",
+            result.name,
+            milliseconds(result.duration)
+        ),
+    }
+}
+
+fn report_pretty(engine: &Engine, results: &[TestResult]) {
+    let stats = engine.cache_stats();
+    println!(
+        "Parse cache: {} hits, {} misses ({}% hit rate)",
+        stats.hits,
+        stats.misses,
+        stats.hit_percentage()
+    );
+
+    let mut slowest: Vec<_> = results.iter().collect();
+    slowest.sort_by_key(|result| std::cmp::Reverse(result.duration));
+    println!("Slowest tests:");
+    for result in slowest.iter().take(SLOWEST_TESTS) {
+        println!("  {:>10} {}", milliseconds(result.duration), result.name);
+    }
+
+    let failed: Vec<_> = results.iter().filter(|r| r.outcome.is_err()).collect();
+    if !failed.is_empty() {
+        eprintln!("The following tests failed:");
+        for test in failed {
+            eprintln!("- {}", test.name);
+        }
+    }
+}
+
+fn tap(results: &[TestResult]) -> String {
+    let mut output = format!("TAP version 13\n1..{}\n", results.len());
+    for (i, result) in results.iter().enumerate() {
+        match &result.outcome {
+            Ok(()) => {
+                let _ = writeln!(output, "ok {} - {}", i + 1, result.name);
+                let _ = writeln!(output, "# time {}", milliseconds(result.duration));
+            }
+            Err(e) => {
+                let _ = writeln!(output, "not ok {} - {}", i + 1, result.name);
+                let _ = writeln!(output, "# time {}", milliseconds(result.duration));
+                for line in e.lines() {
+                    let _ = writeln!(output, "# {line}");
+                }
+            }
+        }
+    }
+    output
+}
+
+fn junit(results: &[TestResult]) -> String {
+    let failures = results.iter().filter(|r| r.outcome.is_err()).count();
+    let mut output = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuite name=\"icypeas\" tests=\"{}\" failures=\"{failures}\">\n",
+        results.len()
+    );
+    for result in results {
+        let name = escape_xml(&result.name);
+        let time = result.duration.as_secs_f64();
+        match &result.outcome {
+            Ok(()) => {
+                let _ = writeln!(output, "  <testcase name=\"{name}\" time=\"{time:.3}\"/>");
+            }
+            Err(e) => {
+                let _ = writeln!(
+                    output,
+                    "  <testcase name=\"{name}\" time=\"{time:.3}\">\n    <failure message=\"{}\"/>\n  </testcase>",
+                    escape_xml(e)
+                );
+            }
+        }
+    }
+    output.push_str("</testsuite>\n");
+    output
+}
+
+fn json(results: &[TestResult]) -> String {
+    let tests: Vec<_> = results
+        .iter()
+        .map(|result| {
+            let name = escape_json(&result.name);
+            let millis = result.duration.as_millis();
+            match &result.outcome {
+                Ok(()) => format!("{{\"name\":\"{name}\",\"passed\":true,\"millis\":{millis}}}"),
+                Err(e) => format!(
+                    "{{\"name\":\"{name}\",\"passed\":false,\"millis\":{millis},\"message\":\"{}\"}}",
+                    escape_json(e)
+                ),
+            }
+        })
+        .collect();
+    format!("[{}]", tests.join(","))
+}
+
+fn milliseconds(duration: Duration) -> String {
+    format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace(|c: char| c.is_control() && c != '\n', "")
+}
+
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
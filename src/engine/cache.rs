@@ -0,0 +1,88 @@
+use crate::error::Result;
+use crate::lexer::Lexer;
+use crate::model::{Located, Statement};
+use crate::parser::Parser;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+pub struct ParseCache {
+    entries: RefCell<HashMap<u64, Entry>>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+struct Entry {
+    source: String,
+    ast: Vec<Located<Statement>>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParseTimes {
+    pub lex: Duration,
+    pub parse: Duration,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl ParseCache {
+    pub fn parse(&self, source: &str) -> Result<Vec<Located<Statement>>> {
+        self.parse_timed(source).map(|(ast, _)| ast)
+    }
+
+    pub fn parse_timed(&self, source: &str) -> Result<(Vec<Located<Statement>>, ParseTimes)> {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        let key = hasher.finish();
+
+        if let Some(entry) = self.entries.borrow().get(&key)
+            && entry.source == source
+        {
+            self.hits.set(self.hits.get() + 1);
+            return Ok((entry.ast.clone(), ParseTimes::default()));
+        }
+        self.misses.set(self.misses.get() + 1);
+
+        let start = Instant::now();
+        let mut lexer = Lexer::new();
+        let tokens = lexer.lex(source)?;
+        let parse_start = Instant::now();
+        let mut parser = Parser::new();
+        let ast = parser.parse(tokens)?;
+        let times = ParseTimes {
+            lex: parse_start - start,
+            parse: parse_start.elapsed(),
+        };
+
+        self.entries.borrow_mut().insert(
+            key,
+            Entry {
+                source: source.to_string(),
+                ast: ast.clone(),
+            },
+        );
+        Ok((ast, times))
+    }
+
+    pub const fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+        }
+    }
+}
+
+impl CacheStats {
+    pub const fn hit_percentage(self) -> usize {
+        match (self.hits * 100).checked_div(self.hits + self.misses) {
+            Some(percentage) => percentage,
+            None => 0,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+pub mod cache;
+pub mod phases;
+
+pub use cache::{CacheStats, ParseCache, ParseTimes};
+pub use phases::Phases;
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::explain::{Explainer, Explanation};
+use crate::interpreter::{CancelHandle, Environment, Evaluation, Interpreter, arguments, transfer};
+use crate::lexer::Lexer;
+use crate::model::{Located, Location, Statement, Transfer, Value};
+use crate::parser::Parser;
+use crate::resolver;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Timing {
+    pub file: Option<PathBuf>,
+    pub times: ParseTimes,
+    pub interpret: Duration,
+}
+
+pub struct TestCase {
+    pub name: String,
+    pub result: Result<()>,
+    pub duration: Duration,
+}
+
+pub struct TestRun {
+    pub result: Result<()>,
+    pub output: String,
+    pub tests: Vec<TestCase>,
+}
+
+pub struct Engine {
+    environment: Rc<RefCell<Environment>>,
+    cache: Rc<ParseCache>,
+    color: bool,
+    lazy_effect_warnings: bool,
+    evaluation: Evaluation,
+    cancel: CancelHandle,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        let environment = Environment::new();
+        environment.borrow_mut().add_builtins();
+        Self {
+            environment,
+            cache: Rc::default(),
+            color: true,
+            lazy_effect_warnings: false,
+            evaluation: Evaluation::Lazy,
+            cancel: CancelHandle::default(),
+        }
+    }
+
+    pub fn with_arguments(program: String, arguments: Vec<String>) -> Self {
+        let engine = Self::new();
+        engine
+            .environment
+            .borrow_mut()
+            .set("args".to_string(), arguments::record(program, arguments));
+        engine
+    }
+
+    pub const fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub const fn with_lazy_effect_warnings(mut self, enabled: bool) -> Self {
+        self.lazy_effect_warnings = enabled;
+        self
+    }
+
+    pub const fn with_evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = evaluation;
+        self
+    }
+
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    pub fn run(&self, source: &str, file: Option<PathBuf>) -> Result<()> {
+        let ast = self.cache.parse(source)?;
+        let mut interpreter = self.fork(source, file);
+        let result = interpreter.interpret(ast);
+        interpreter.run_exit_hooks(result)
+    }
+
+    pub fn run_timed(&self, source: &str, file: Option<PathBuf>) -> (Result<()>, Vec<Timing>) {
+        let mut interpreter = self.fork(source, file.clone());
+        interpreter.record_timings();
+        let result = self.cache.parse_timed(source).and_then(|(ast, times)| {
+            let start = Instant::now();
+            let result = interpreter.interpret(ast);
+            interpreter.push_timing(Timing {
+                file,
+                times,
+                interpret: start.elapsed(),
+            });
+            result
+        });
+        let result = interpreter.run_exit_hooks(result);
+        (result, interpreter.take_timings())
+    }
+
+    pub fn run_phased(&self, source: &str, file: Option<PathBuf>) -> (Result<()>, Phases) {
+        let mut phases = Phases::default();
+        let mut interpreter = self.fork(source, file);
+        let result = Self::phases(source, &mut interpreter, &mut phases);
+        (interpreter.run_exit_hooks(result), phases)
+    }
+
+    fn phases(source: &str, interpreter: &mut Interpreter, phases: &mut Phases) -> Result<()> {
+        let tokens = phases.time("lex", "tokens", || {
+            let tokens = Lexer::new().lex(source);
+            let count = tokens.as_ref().map_or(0, Vec::len);
+            (tokens, count)
+        })?;
+        let ast = phases.time("parse", "statements", || {
+            let ast = Parser::new().parse(tokens);
+            let count = ast.as_ref().map_or(0, Vec::len);
+            (ast, count)
+        })?;
+        phases.time("resolve", "references", || {
+            ((), resolver::resolve(&ast).references.len())
+        });
+        phases.time("check", "definitions", || {
+            let count = ast
+                .iter()
+                .filter(|statement| statement.node.defined_name().is_some())
+                .count();
+            (Interpreter::check(&ast), count)
+        })?;
+        phases.time("eval", "statements", || {
+            let count = ast.len();
+            (interpreter.execute_all(ast), count)
+        })
+    }
+
+    pub fn call(
+        &self,
+        source: &str,
+        file: Option<PathBuf>,
+        function: &str,
+        argument: Transfer,
+        location: Location,
+    ) -> Result<Transfer> {
+        let statements = self.cache.parse(source)?;
+        let mut interpreter = self.fork(source, file);
+        interpreter.interpret(
+            statements
+                .into_iter()
+                .filter(|statement| !matches!(statement.node, Statement::Expression { .. }))
+                .collect(),
+        )?;
+        transfer::call(&mut interpreter, function, argument, &Rc::new(location))
+    }
+
+    pub fn eval(&self, source: &str) -> Result<Value> {
+        let ast = self.cache.parse(source)?;
+        self.fork(source, None).interpret_value(ast)
+    }
+
+    pub fn explain(
+        &self,
+        source: &str,
+        file: Option<PathBuf>,
+        expression: &str,
+        limit: usize,
+    ) -> Result<Explanation> {
+        let statements = self.cache.parse(source)?;
+        let expression = match self.cache.parse(expression)?.pop() {
+            Some(Located {
+                node: Statement::Expression { expression },
+                ..
+            }) => expression,
+            Some(statement) => {
+                return Err(Error::with_help(
+                    ErrorKind::ExpectedExpression,
+                    statement.location,
+                    "Only expressions can be explained",
+                ));
+            }
+            None => return Err(ErrorKind::UnexpectedEndOfFile.into()),
+        };
+
+        let mut interpreter = self.fork(source, file);
+        interpreter.interpret(
+            statements
+                .iter()
+                .filter(|statement| !matches!(statement.node, Statement::Expression { .. }))
+                .cloned()
+                .collect(),
+        )?;
+        Ok(Explainer::new(&mut interpreter, &statements).explain(expression, limit))
+    }
+
+    pub fn eval_with_deadline(&self, source: &str, timeout: Duration) -> Result<Value> {
+        let ast = self.cache.parse(source)?;
+        let mut interpreter = self.fork(source, None);
+        interpreter.set_deadline(Instant::now() + timeout);
+        interpreter.interpret_value(ast)
+    }
+
+    pub fn run_test(&self, source: &str, file: Option<PathBuf>, timeout: Duration) -> TestRun {
+        let mut interpreter = self.fork(source, file);
+        interpreter.set_deadline(Instant::now() + timeout);
+        interpreter.capture_output();
+        interpreter.disable_input();
+        interpreter.collect_tests();
+        let result = self
+            .cache
+            .parse(source)
+            .and_then(|ast| interpreter.interpret(ast));
+        let result = interpreter.run_exit_hooks(result);
+        let tests = if result.is_ok() {
+            interpreter.run_tests()
+        } else {
+            vec![]
+        };
+        TestRun {
+            result,
+            output: interpreter.take_output(),
+            tests,
+        }
+    }
+
+    pub fn run_examples(
+        &self,
+        source: &str,
+        file: Option<PathBuf>,
+        examples: Vec<(String, String)>,
+        timeout: Duration,
+    ) -> Result<Vec<TestCase>> {
+        let mut interpreter = self.fork(source, file);
+        interpreter.set_deadline(Instant::now() + timeout);
+        interpreter.capture_output();
+        interpreter.disable_input();
+        interpreter.interpret(self.cache.parse(source)?)?;
+
+        Ok(examples
+            .into_iter()
+            .map(|(name, example)| {
+                let start = Instant::now();
+                let result = self
+                    .cache
+                    .parse(&example)
+                    .and_then(|ast| interpreter.interpret(ast));
+                TestCase {
+                    name,
+                    result,
+                    duration: start.elapsed(),
+                }
+            })
+            .collect())
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    fn fork(&self, source: &str, file: Option<PathBuf>) -> Interpreter {
+        let environment = Environment::with_parent(self.environment.clone());
+        let mut interpreter = Interpreter::with_file(environment, file.clone(), self.cache.clone());
+        interpreter.add_module(file, source);
+        if !self.color {
+            interpreter.disable_color();
+        }
+        if self.lazy_effect_warnings {
+            interpreter.warn_lazy_effects();
+        }
+        interpreter.set_evaluation(self.evaluation);
+        interpreter.set_cancel_handle(self.cancel.clone());
+        interpreter
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct Phase {
+    pub name: &'static str,
+    pub duration: Duration,
+    pub items: usize,
+    pub unit: &'static str,
+}
+
+#[derive(Debug, Default)]
+pub struct Phases {
+    phases: Vec<Phase>,
+}
+
+impl Phases {
+    pub fn time<T>(
+        &mut self,
+        name: &'static str,
+        unit: &'static str,
+        phase: impl FnOnce() -> (T, usize),
+    ) -> T {
+        let start = Instant::now();
+        let (result, items) = phase();
+        self.phases.push(Phase {
+            name,
+            duration: start.elapsed(),
+            items,
+            unit,
+        });
+        result
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Phase> {
+        self.phases.iter()
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.duration).sum()
+    }
+}
@@ -15,9 +15,14 @@ macro_rules! err {
 
 #[derive(Debug)]
 pub enum ErrorKind {
+    AssertionFailed,
+    Cancelled,
     DivisionByZero,
     ExpectedExpression,
+    Exit(u8),
+    Hole,
     IncompleteIf,
+    Interrupted,
     InvalidArguments,
     InvalidIdentifier,
     InvalidToken,
@@ -28,9 +33,11 @@ pub enum ErrorKind {
     Overflow,
     UndeclaredFunction,
     UnexpectedEndOfFile,
+    Timeout,
     UnexpectedToken,
     UnimplementedFunction,
     UnsupportedExpression,
+    UnterminatedComment,
     UnterminatedString,
     UnterminatedUse,
 }
@@ -40,14 +47,28 @@ pub struct Error {
     pub kind: ErrorKind,
     pub location: Option<Rc<Location>>,
     pub help: Option<String>,
+    pub suggestions: Vec<Suggestion>,
+    pub context: Vec<(String, Rc<Location>)>,
+}
+
+#[derive(Debug)]
+pub struct Suggestion {
+    pub location: Rc<Location>,
+    pub length: usize,
+    pub replacement: String,
 }
 
 impl Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let message = match self {
+            Self::AssertionFailed => "Assertion failed",
+            Self::Cancelled => "Cancelled",
             Self::DivisionByZero => "Division by zero",
             Self::ExpectedExpression => "Expected expression",
+            Self::Exit(code) => return write!(f, "Exited with code {code}"),
+            Self::Hole => "Found hole",
             Self::IncompleteIf => "Incomplete if",
+            Self::Interrupted => "Interrupted",
             Self::InvalidArguments => "Invalid arguments",
             Self::InvalidIdentifier => "Invalid identifier",
             Self::InvalidToken => "Invalid token",
@@ -58,9 +79,11 @@ impl Display for ErrorKind {
             Self::Overflow => "Overflow",
             Self::UndeclaredFunction => "Undeclared function",
             Self::UnexpectedEndOfFile => "Unexpected end of file",
+            Self::Timeout => "Timeout",
             Self::UnexpectedToken => "Unexpected token",
             Self::UnimplementedFunction => "Unimplemented function",
             Self::UnsupportedExpression => "Unsupported Expression",
+            Self::UnterminatedComment => "Unterminated comment",
             Self::UnterminatedString => "Unterminated string",
             Self::UnterminatedUse => "Unterminated use",
         };
@@ -76,9 +99,35 @@ impl Display for Error {
         );
 
         if let Some(help) = &self.help {
-            write!(f, "{} {}\nHelp: {}", self.kind, location_str, help)
+            write!(f, "{} {}\nHelp: {}", self.kind, location_str, help)?;
+        } else {
+            write!(f, "{} {}", self.kind, location_str)?;
+        }
+
+        for suggestion in &self.suggestions {
+            write!(f, "\n{suggestion}")?;
+        }
+        for (message, location) in &self.context {
+            write!(f, "\n  while {message} at {location}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.length == 0 {
+            write!(
+                f,
+                "Suggestion: insert `{}` at {}",
+                self.replacement, self.location
+            )
         } else {
-            write!(f, "{} {}", self.kind, location_str)
+            write!(
+                f,
+                "Suggestion: replace with `{}` at {}",
+                self.replacement, self.location
+            )
         }
     }
 }
@@ -89,6 +138,8 @@ impl Error {
             kind,
             location: Some(location),
             help: None,
+            suggestions: Vec::new(),
+            context: Vec::new(),
         }
     }
 
@@ -97,8 +148,29 @@ impl Error {
             kind,
             location: Some(location),
             help: Some(help.into()),
+            suggestions: Vec::new(),
+            context: Vec::new(),
         }
     }
+
+    pub fn suggest(
+        mut self,
+        location: Rc<Location>,
+        length: usize,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            location,
+            length,
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    pub fn context(mut self, message: impl Into<String>, location: Rc<Location>) -> Self {
+        self.context.push((message.into(), location));
+        self
+    }
 }
 
 impl From<ErrorKind> for Error {
@@ -107,6 +179,8 @@ impl From<ErrorKind> for Error {
             kind,
             location: None,
             help: None,
+            suggestions: Vec::new(),
+            context: Vec::new(),
         }
     }
 }
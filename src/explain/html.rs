@@ -0,0 +1,97 @@
+use super::{Explanation, print};
+use std::fmt::Write;
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em auto; max-width: 60em; }
+pre { font-size: 1.4em; padding: 1em; background: #f4f4f4; white-space: pre-wrap; }
+mark { background: #ffe08a; }
+ol { font-family: monospace; color: #777; }
+li.current { color: #000; font-weight: bold; }
+button { font-size: 1em; margin-right: 0.5em; }";
+
+const SCRIPT: &str = "const steps = document.querySelectorAll('template');
+const items = document.querySelectorAll('li');
+const view = document.getElementById('step');
+const counter = document.getElementById('counter');
+let current = 0;
+function show(index) {
+    current = Math.max(0, Math.min(steps.length - 1, index));
+    view.innerHTML = steps[current].innerHTML;
+    counter.textContent = `step ${current} of ${steps.length - 1}`;
+    items.forEach((item, i) => item.className = i === current ? 'current' : '');
+}
+document.getElementById('previous').onclick = () => show(current - 1);
+document.getElementById('next').onclick = () => show(current + 1);
+document.addEventListener('keydown', event => {
+    if (event.key === 'ArrowLeft') show(current - 1);
+    if (event.key === 'ArrowRight') show(current + 1);
+});
+items.forEach((item, i) => item.onclick = () => show(i));
+show(0);";
+
+pub fn render(explanation: &Explanation, limit: usize) -> String {
+    let steps: Vec<_> = explanation.steps.iter().map(print).collect();
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>{}</h1>\n\
+         <button id=\"previous\">&larr; previous</button><button id=\"next\">next &rarr;</button>\
+         <span id=\"counter\"></span>\n<pre id=\"step\"></pre>\n",
+        escape(&steps[0]),
+        escape(&steps[0]),
+    );
+
+    for (i, step) in steps.iter().enumerate() {
+        let previous = i.checked_sub(1).map_or("", |i| steps[i].as_str());
+        let _ = writeln!(html, "<template>{}</template>", highlight(previous, step));
+    }
+
+    html.push_str("<ol start=\"0\">\n");
+    for step in &steps {
+        let _ = writeln!(html, "<li>{}</li>", escape(step));
+    }
+    html.push_str("</ol>\n");
+    if explanation.truncated {
+        let _ = writeln!(html, "<p>Stopped after {limit} steps.</p>");
+    }
+    if let Some(error) = &explanation.error {
+        let _ = writeln!(html, "<p>Error: {}</p>", escape(&error.to_string()));
+    }
+    let _ = write!(html, "<script>\n{SCRIPT}\n</script>\n</body>\n</html>\n");
+    html
+}
+
+fn highlight(previous: &str, current: &str) -> String {
+    if previous.is_empty() {
+        return escape(current);
+    }
+    let prefix = previous
+        .char_indices()
+        .zip(current.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or_else(|| previous.len().min(current.len()), |((i, _), _)| i);
+    let suffix = previous[prefix..]
+        .chars()
+        .rev()
+        .zip(current[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(_, b)| b.len_utf8())
+        .sum::<usize>();
+    let end = current.len() - suffix;
+    if prefix == end {
+        return escape(current);
+    }
+    format!(
+        "{}<mark>{}</mark>{}",
+        escape(&current[..prefix]),
+        escape(&current[prefix..end]),
+        escape(&current[end..])
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
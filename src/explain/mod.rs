@@ -0,0 +1,725 @@
+pub mod html;
+mod printer;
+
+pub use printer::print;
+
+use crate::error::{Error, Result};
+use crate::interpreter::Interpreter;
+use crate::model::{
+    Expression, Located, LocatedExt, Location, Statement, Token, TokenKind, TokenValue, Value,
+    ValueKey,
+};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::slice;
+
+pub struct Explanation {
+    pub steps: Vec<Located<Expression>>,
+    pub truncated: bool,
+    pub error: Option<Error>,
+}
+
+pub struct Explainer<'a> {
+    interpreter: &'a mut Interpreter,
+    definitions: HashMap<String, Located<Expression>>,
+    fresh: Cell<usize>,
+}
+
+impl<'a> Explainer<'a> {
+    pub fn new(interpreter: &'a mut Interpreter, statements: &[Located<Statement>]) -> Self {
+        let mut definitions = HashMap::new();
+        collect_definitions(statements, &mut definitions);
+        Self {
+            interpreter,
+            definitions,
+            fresh: Cell::new(0),
+        }
+    }
+
+    pub fn explain(&mut self, expression: Located<Expression>, limit: usize) -> Explanation {
+        let mut explanation = Explanation {
+            steps: vec![expression],
+            truncated: false,
+            error: None,
+        };
+        let mut count = 0;
+        loop {
+            let current = &explanation.steps[explanation.steps.len() - 1];
+            match self.step(current) {
+                Ok(Some(_)) if count == limit => {
+                    explanation.truncated = true;
+                    return explanation;
+                }
+                Ok(Some(next)) => {
+                    if print(&next) == print(current) {
+                        explanation.steps.pop();
+                    }
+                    explanation.steps.push(next);
+                    count += 1;
+                }
+                Ok(None) => return explanation,
+                Err(error) => {
+                    explanation.error = Some(error);
+                    return explanation;
+                }
+            }
+        }
+    }
+
+    fn step(&mut self, expression: &Located<Expression>) -> Result<Option<Located<Expression>>> {
+        let location = expression.location.clone();
+        match &expression.node {
+            Expression::Literal { .. } | Expression::Lambda { .. } | Expression::Match { .. } => {
+                Ok(None)
+            }
+            Expression::Identifier { token } => Ok(self
+                .definition(token)
+                .filter(|definition| !matches!(definition.node, Expression::Lambda { .. }))
+                .cloned()),
+            Expression::Unary {
+                operator,
+                expression: operand,
+            } => self.step(operand)?.map_or_else(
+                || self.compute(expression),
+                |operand| {
+                    Ok(Some(
+                        Expression::Unary {
+                            operator: operator.clone(),
+                            expression: Box::new(operand),
+                        }
+                        .at(location),
+                    ))
+                },
+            ),
+            Expression::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                let (left, right) = if let Some(left) = self.step(left)? {
+                    (left, right.as_ref().clone())
+                } else if let Some(right) = self.step(right)? {
+                    (left.as_ref().clone(), right)
+                } else {
+                    return self.compute(expression);
+                };
+                Ok(Some(
+                    Expression::Binary {
+                        left: Box::new(left),
+                        operator: operator.clone(),
+                        right: Box::new(right),
+                    }
+                    .at(location),
+                ))
+            }
+            Expression::Block { statements } => self.step_block(statements, expression),
+            Expression::Call { .. } => self.step_call(expression),
+            Expression::If {
+                branches,
+                otherwise,
+            } => self.step_if(branches, otherwise, expression),
+            Expression::Let { name, value, body } => {
+                let name = name.node.get_identifier_name().unwrap_or_default();
+                Ok(Some(substitute(
+                    body,
+                    &HashMap::from([(name, value.as_ref().clone())]),
+                    &self.fresh,
+                )))
+            }
+            Expression::List { elements } => {
+                let mut elements = elements.clone();
+                for element in &mut elements {
+                    if let Some(next) = self.step(element)? {
+                        *element = next;
+                        return Ok(Some(Expression::List { elements }.at(location)));
+                    }
+                }
+                Ok(None)
+            }
+            Expression::Record { fields } => {
+                let mut fields = fields.clone();
+                for (_, value) in &mut fields {
+                    if let Some(next) = self.step(value)? {
+                        *value = next;
+                        return Ok(Some(Expression::Record { fields }.at(location)));
+                    }
+                }
+                Ok(None)
+            }
+            Expression::Field { record, field } => {
+                if let Expression::Record { fields } = &record.node {
+                    return Ok(fields
+                        .iter()
+                        .find(|(name, _)| name.node.value == field.node.value)
+                        .map(|(_, value)| value.clone()));
+                }
+                self.step(record)?.map_or_else(
+                    || self.compute(expression),
+                    |record| {
+                        Ok(Some(
+                            Expression::Field {
+                                record: Box::new(record),
+                                field: field.clone(),
+                            }
+                            .at(location),
+                        ))
+                    },
+                )
+            }
+        }
+    }
+
+    fn step_block(
+        &mut self,
+        statements: &[Located<Statement>],
+        block: &Located<Expression>,
+    ) -> Result<Option<Located<Expression>>> {
+        let Statement::Expression { expression } = &statements[0].node else {
+            return self.compute(block);
+        };
+        let mut statements = statements.to_vec();
+        if let Some(next) = self.step(expression)? {
+            statements[0] =
+                Statement::Expression { expression: next }.at(statements[0].location.clone());
+        } else if statements.len() == 1 {
+            return Ok(Some(expression.clone()));
+        } else {
+            statements.remove(0);
+        }
+        Ok(Some(
+            Expression::Block { statements }.at(block.location.clone()),
+        ))
+    }
+
+    fn step_if(
+        &mut self,
+        branches: &[(Located<Expression>, Located<Expression>)],
+        otherwise: &Located<Expression>,
+        expression: &Located<Expression>,
+    ) -> Result<Option<Located<Expression>>> {
+        let (condition, body) = &branches[0];
+        if let Expression::Literal { token } = &condition.node {
+            if token.node.value == TokenValue::Boolean(true) {
+                return Ok(Some(body.clone()));
+            }
+            if branches.len() == 1 {
+                return Ok(Some(otherwise.clone()));
+            }
+            return Ok(Some(
+                Expression::If {
+                    branches: branches[1..].to_vec(),
+                    otherwise: Box::new(otherwise.clone()),
+                }
+                .at(expression.location.clone()),
+            ));
+        }
+
+        self.step(condition)?.map_or_else(
+            || self.compute(expression),
+            |condition| {
+                let mut branches = branches.to_vec();
+                branches[0].0 = condition;
+                Ok(Some(
+                    Expression::If {
+                        branches,
+                        otherwise: Box::new(otherwise.clone()),
+                    }
+                    .at(expression.location.clone()),
+                ))
+            },
+        )
+    }
+
+    fn step_call(&mut self, call: &Located<Expression>) -> Result<Option<Located<Expression>>> {
+        let mut arguments = vec![];
+        let mut head = call;
+        while let Expression::Call { function, argument } = &head.node {
+            arguments.push(argument.as_ref().clone());
+            head = function;
+        }
+        arguments.reverse();
+
+        if let Expression::Lambda { parameter, body } = &head.node {
+            let name = parameter.node.get_identifier_name().unwrap_or_default();
+            let body = substitute(
+                body,
+                &HashMap::from([(name, arguments[0].clone())]),
+                &self.fresh,
+            );
+            return Ok(Some(apply(body, &arguments[1..])));
+        }
+        if let Expression::Identifier { token } = &head.node
+            && let Some(definition) = self.definition(token).cloned()
+            && let Some(next) = self.unfold(&definition, &arguments, head)?
+        {
+            return Ok(Some(next));
+        }
+
+        if let Some(head) = self.step(head)? {
+            return Ok(Some(apply(head, &arguments)));
+        }
+        for i in 0..arguments.len() {
+            if let Some(next) = self.step(&arguments[i])? {
+                arguments[i] = next;
+                return Ok(Some(apply(head.clone(), &arguments)));
+            }
+        }
+        self.compute(call)
+    }
+
+    fn unfold(
+        &mut self,
+        definition: &Located<Expression>,
+        arguments: &[Located<Expression>],
+        head: &Located<Expression>,
+    ) -> Result<Option<Located<Expression>>> {
+        let mut parameters = vec![];
+        let mut body = definition;
+        while let Expression::Lambda {
+            parameter,
+            body: inner,
+        } = &body.node
+        {
+            parameters.push(parameter.node.get_identifier_name().unwrap_or_default());
+            body = inner;
+        }
+        if parameters.is_empty() || arguments.len() < parameters.len() {
+            return Ok(None);
+        }
+
+        let (applied, rest) = arguments.split_at(parameters.len());
+        let bindings: HashMap<_, _> = parameters.iter().cloned().zip(applied.to_vec()).collect();
+        let Expression::Match {
+            arguments: names,
+            clauses,
+        } = &body.node
+        else {
+            return Ok(Some(apply(substitute(body, &bindings, &self.fresh), rest)));
+        };
+
+        'clauses: for (patterns, clause) in clauses {
+            let mut bindings = bindings.clone();
+            for (pattern, name) in patterns.iter().zip(names) {
+                let name = name.node.get_identifier_name().unwrap_or_default();
+                let Some(index) = parameters.iter().position(|parameter| *parameter == name) else {
+                    return Ok(None);
+                };
+                match pattern.node.kind {
+                    TokenKind::Underscore => {}
+                    TokenKind::Identifier => {
+                        let name = pattern.node.get_identifier_name().unwrap_or_default();
+                        bindings.insert(name, arguments[index].clone());
+                    }
+                    _ => {
+                        let Expression::Literal { token } = &arguments[index].node else {
+                            let Some(next) = self.step(&arguments[index])? else {
+                                return Ok(None);
+                            };
+                            let mut arguments = arguments.to_vec();
+                            arguments[index] = next;
+                            return Ok(Some(apply(head.clone(), &arguments)));
+                        };
+                        let literal = Value::try_from(pattern)?;
+                        if ValueKey::from_value(&literal)
+                            != ValueKey::from_value(&token.try_into()?)
+                        {
+                            continue 'clauses;
+                        }
+                    }
+                }
+            }
+            return Ok(Some(apply(
+                substitute(clause, &bindings, &self.fresh),
+                rest,
+            )));
+        }
+        Ok(None)
+    }
+
+    fn compute(&mut self, expression: &Located<Expression>) -> Result<Option<Located<Expression>>> {
+        let location = expression.location.clone();
+        let statement = Statement::Expression {
+            expression: expression.clone(),
+        }
+        .at(location.clone());
+        let value = self.interpreter.interpret_value(vec![statement])?;
+        Ok(literal(&value, &location))
+    }
+
+    fn definition(&self, token: &Located<Token>) -> Option<&Located<Expression>> {
+        token
+            .node
+            .get_identifier_name()
+            .and_then(|name| self.definitions.get(&name))
+    }
+}
+
+fn collect_definitions(
+    statements: &[Located<Statement>],
+    definitions: &mut HashMap<String, Located<Expression>>,
+) {
+    for statement in statements {
+        match &statement.node {
+            Statement::Attributed { statement, .. } => {
+                collect_definitions(std::slice::from_ref(statement), definitions);
+            }
+            Statement::Mutual {
+                definitions: statements,
+            } => collect_definitions(statements, definitions),
+            Statement::Definition {
+                name,
+                parameter,
+                body,
+            } => {
+                let lambda = Expression::Lambda {
+                    parameter: parameter.clone(),
+                    body: Box::new(body.clone()),
+                }
+                .at(statement.location.clone());
+                definitions.insert(name.node.get_identifier_name().unwrap_or_default(), lambda);
+            }
+            Statement::Variable { name, body } => {
+                definitions.insert(
+                    name.node.get_identifier_name().unwrap_or_default(),
+                    body.clone(),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn apply(function: Located<Expression>, arguments: &[Located<Expression>]) -> Located<Expression> {
+    arguments.iter().fold(function, |function, argument| {
+        let location = function.location.clone();
+        Expression::Call {
+            function: Box::new(function),
+            argument: Box::new(argument.clone()),
+        }
+        .at(location)
+    })
+}
+
+fn substitute(
+    expression: &Located<Expression>,
+    bindings: &HashMap<String, Located<Expression>>,
+    fresh: &Cell<usize>,
+) -> Located<Expression> {
+    let location = expression.location.clone();
+    let recurse =
+        |expression: &Located<Expression>| Box::new(substitute(expression, bindings, fresh));
+
+    match &expression.node {
+        Expression::Identifier { token } => token
+            .node
+            .get_identifier_name()
+            .and_then(|name| bindings.get(&name))
+            .map_or_else(|| expression.clone(), Clone::clone),
+        Expression::Literal { .. } => expression.clone(),
+        Expression::Unary {
+            operator,
+            expression,
+        } => Expression::Unary {
+            operator: operator.clone(),
+            expression: recurse(expression),
+        }
+        .at(location),
+        Expression::Binary {
+            left,
+            operator,
+            right,
+        } => Expression::Binary {
+            left: recurse(left),
+            operator: operator.clone(),
+            right: recurse(right),
+        }
+        .at(location),
+        Expression::Call { function, argument } => Expression::Call {
+            function: recurse(function),
+            argument: recurse(argument),
+        }
+        .at(location),
+        Expression::If {
+            branches,
+            otherwise,
+        } => Expression::If {
+            branches: branches
+                .iter()
+                .map(|(condition, body)| {
+                    (
+                        substitute(condition, bindings, fresh),
+                        substitute(body, bindings, fresh),
+                    )
+                })
+                .collect(),
+            otherwise: recurse(otherwise),
+        }
+        .at(location),
+        Expression::Lambda { parameter, body } => {
+            let (mut parameters, body) = bind(slice::from_ref(parameter), body, bindings, fresh);
+            Expression::Lambda {
+                parameter: parameters.remove(0),
+                body: Box::new(body),
+            }
+            .at(location)
+        }
+        Expression::Let { name, value, body } => {
+            let (mut names, body) = bind(slice::from_ref(name), body, bindings, fresh);
+            Expression::Let {
+                name: names.remove(0),
+                value: recurse(value),
+                body: Box::new(body),
+            }
+            .at(location)
+        }
+        Expression::Block { statements } => Expression::Block {
+            statements: substitute_block(statements, bindings, fresh),
+        }
+        .at(location),
+        Expression::List { elements } => Expression::List {
+            elements: substitute_all(elements, bindings, fresh),
+        }
+        .at(location),
+        Expression::Record { fields } => Expression::Record {
+            fields: fields
+                .iter()
+                .map(|(name, value)| (name.clone(), substitute(value, bindings, fresh)))
+                .collect(),
+        }
+        .at(location),
+        Expression::Field { record, field } => Expression::Field {
+            record: recurse(record),
+            field: field.clone(),
+        }
+        .at(location),
+        Expression::Match { arguments, clauses } => Expression::Match {
+            arguments: arguments.clone(),
+            clauses: clauses
+                .iter()
+                .map(|(patterns, body)| bind(patterns, body, bindings, fresh))
+                .collect(),
+        }
+        .at(location),
+    }
+}
+
+fn substitute_all(
+    expressions: &[Located<Expression>],
+    bindings: &HashMap<String, Located<Expression>>,
+    fresh: &Cell<usize>,
+) -> Vec<Located<Expression>> {
+    expressions
+        .iter()
+        .map(|expression| substitute(expression, bindings, fresh))
+        .collect()
+}
+
+fn substitute_block(
+    statements: &[Located<Statement>],
+    bindings: &HashMap<String, Located<Expression>>,
+    fresh: &Cell<usize>,
+) -> Vec<Located<Statement>> {
+    let names: Vec<_> = statements
+        .iter()
+        .filter_map(|statement| statement.node.defined_name().cloned())
+        .collect();
+    let (names, bindings) = rebind(&names, bindings, fresh);
+    let renamed = |name: &Located<Token>| {
+        names
+            .iter()
+            .find(|renamed| renamed.location == name.location)
+            .unwrap_or(name)
+            .clone()
+    };
+    statements
+        .iter()
+        .map(|statement| {
+            let node = match &statement.node {
+                Statement::Expression { expression } => Statement::Expression {
+                    expression: substitute(expression, &bindings, fresh),
+                },
+                Statement::Variable { name, body } => Statement::Variable {
+                    name: renamed(name),
+                    body: substitute(body, &bindings, fresh),
+                },
+                Statement::Definition {
+                    name,
+                    parameter,
+                    body,
+                } => {
+                    let (mut parameters, body) =
+                        bind(slice::from_ref(parameter), body, &bindings, fresh);
+                    Statement::Definition {
+                        name: renamed(name),
+                        parameter: parameters.remove(0),
+                        body,
+                    }
+                }
+                other => other.clone(),
+            };
+            node.at(statement.location.clone())
+        })
+        .collect()
+}
+
+fn bind(
+    binders: &[Located<Token>],
+    body: &Located<Expression>,
+    bindings: &HashMap<String, Located<Expression>>,
+    fresh: &Cell<usize>,
+) -> (Vec<Located<Token>>, Located<Expression>) {
+    let (binders, bindings) = rebind(binders, bindings, fresh);
+    let body = substitute(body, &bindings, fresh);
+    (binders, body)
+}
+
+fn rebind(
+    binders: &[Located<Token>],
+    bindings: &HashMap<String, Located<Expression>>,
+    fresh: &Cell<usize>,
+) -> (Vec<Located<Token>>, HashMap<String, Located<Expression>>) {
+    let names = binders
+        .iter()
+        .map(|binder| binder.node.get_identifier_name());
+    let mut bindings = without(bindings, names);
+    let mut mentioned = HashSet::new();
+    for value in bindings.values() {
+        mentions(value, &mut mentioned);
+    }
+
+    let binders = binders
+        .iter()
+        .map(|binder| match binder.node.get_identifier_name() {
+            Some(name) if mentioned.contains(&name) => {
+                fresh.set(fresh.get() + 1);
+                let renamed = Token::new(
+                    TokenKind::Identifier,
+                    TokenValue::Identifier(format!("{name}_{}", fresh.get())),
+                )
+                .at(binder.location.clone());
+                bindings.insert(
+                    name,
+                    Expression::Identifier {
+                        token: renamed.clone(),
+                    }
+                    .at(binder.location.clone()),
+                );
+                renamed
+            }
+            _ => binder.clone(),
+        })
+        .collect();
+    (binders, bindings)
+}
+
+fn mentions(expression: &Located<Expression>, names: &mut HashSet<String>) {
+    let mut visit = |expression: &Located<Expression>| mentions(expression, names);
+    match &expression.node {
+        Expression::Identifier { token } => names.extend(token.node.get_identifier_name()),
+        Expression::Literal { .. } => {}
+        Expression::Unary { expression, .. } => visit(expression),
+        Expression::Field { record, .. } => visit(record),
+        Expression::Lambda { body, .. } => visit(body),
+        Expression::Binary { left, right, .. } => {
+            visit(left);
+            visit(right);
+        }
+        Expression::Call { function, argument } => {
+            visit(function);
+            visit(argument);
+        }
+        Expression::Let { value, body, .. } => {
+            visit(value);
+            visit(body);
+        }
+        Expression::If {
+            branches,
+            otherwise,
+        } => {
+            for (condition, body) in branches {
+                visit(condition);
+                visit(body);
+            }
+            visit(otherwise);
+        }
+        Expression::List { elements } => elements.iter().for_each(visit),
+        Expression::Record { fields } => fields.iter().for_each(|(_, value)| visit(value)),
+        Expression::Match { arguments, clauses } => {
+            names.extend(
+                arguments
+                    .iter()
+                    .filter_map(|a| a.node.get_identifier_name()),
+            );
+            for (_, body) in clauses {
+                mentions(body, names);
+            }
+        }
+        Expression::Block { statements } => {
+            for statement in statements {
+                match &statement.node {
+                    Statement::Expression { expression: body }
+                    | Statement::Variable { body, .. }
+                    | Statement::Definition { body, .. } => mentions(body, names),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn without(
+    bindings: &HashMap<String, Located<Expression>>,
+    names: impl IntoIterator<Item = Option<String>>,
+) -> HashMap<String, Located<Expression>> {
+    let mut bindings = bindings.clone();
+    for name in names.into_iter().flatten() {
+        bindings.remove(&name);
+    }
+    bindings
+}
+
+fn literal(value: &Value, location: &Rc<Location>) -> Option<Located<Expression>> {
+    let token = |kind, value| Token::new(kind, value).at(location.clone());
+    let expression = match value {
+        Value::Boolean(boolean) => Expression::Literal {
+            token: token(
+                if *boolean {
+                    TokenKind::True
+                } else {
+                    TokenKind::False
+                },
+                TokenValue::Boolean(*boolean),
+            ),
+        },
+        Value::Integer(integer) => Expression::Literal {
+            token: token(TokenKind::Integer, TokenValue::Integer(*integer)),
+        },
+        Value::Float(float) => Expression::Literal {
+            token: token(TokenKind::Float, TokenValue::Float(*float)),
+        },
+        Value::String(string) => Expression::Literal {
+            token: token(TokenKind::String, TokenValue::String(string.clone())),
+        },
+        Value::None => Expression::Literal {
+            token: token(TokenKind::Null, TokenValue::None),
+        },
+        Value::List(elements) => Expression::List {
+            elements: elements
+                .iter()
+                .map(|element| literal(element, location))
+                .collect::<Option<_>>()?,
+        },
+        Value::Record(fields) => Expression::Record {
+            fields: fields
+                .iter()
+                .map(|(name, value)| {
+                    let name = token(TokenKind::Identifier, TokenValue::Identifier(name.clone()));
+                    literal(value, location).map(|value| (name, value))
+                })
+                .collect::<Option<_>>()?,
+        },
+        _ => return None,
+    };
+    Some(expression.at(location.clone()))
+}
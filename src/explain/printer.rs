@@ -0,0 +1,251 @@
+use crate::model::{Expression, Located, Statement, Token, TokenKind, TokenValue};
+use crate::parser::Precedence;
+
+const ATOMIC: u8 = u8::MAX;
+
+pub fn print(expression: &Located<Expression>) -> String {
+    let mut output = String::new();
+    write(&mut output, expression, 0);
+    output
+}
+
+fn write(output: &mut String, expression: &Located<Expression>, minimum: u8) {
+    let parenthesize = rank(&expression.node) < minimum;
+    if parenthesize {
+        output.push('(');
+    }
+
+    match &expression.node {
+        Expression::Unary {
+            operator,
+            expression,
+        } => {
+            output.push_str(symbol(operator.node.kind));
+            write(output, expression, Precedence::Prefix as u8 + 1);
+        }
+        Expression::Binary {
+            left,
+            operator,
+            right,
+        } => {
+            let precedence = Precedence::from(operator.node.kind) as u8;
+            let (left_minimum, right_minimum) = if operator.node.kind == TokenKind::Colon {
+                (precedence + 1, precedence)
+            } else {
+                (precedence, precedence + 1)
+            };
+            write(output, left, left_minimum);
+            output.push(' ');
+            output.push_str(symbol(operator.node.kind));
+            output.push(' ');
+            write(output, right, right_minimum);
+        }
+        Expression::Block { statements } => {
+            output.push_str("do ");
+            for (i, statement) in statements.iter().enumerate() {
+                if i > 0 {
+                    output.push_str("; ");
+                }
+                write_statement(output, statement);
+            }
+            output.push_str(" end");
+        }
+        Expression::Call { function, argument } => {
+            write(output, function, Precedence::Application as u8);
+            output.push(' ');
+            write(output, argument, ATOMIC);
+        }
+        Expression::Identifier { token } | Expression::Literal { token } => {
+            output.push_str(&token_text(token));
+        }
+        Expression::If {
+            branches,
+            otherwise,
+        } => write_if(output, branches, otherwise),
+        Expression::Lambda { parameter, body } => write_lambda(output, parameter, body),
+        Expression::Let { name, value, body } => {
+            output.push_str("let ");
+            output.push_str(&token_text(name));
+            output.push_str(" = ");
+            write(output, value, 0);
+            output.push_str(" in ");
+            write(output, body, 0);
+        }
+        Expression::List { elements } => {
+            output.push('[');
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                write(output, element, 0);
+            }
+            output.push(']');
+        }
+        Expression::Record { fields } => {
+            output.push('{');
+            for (i, (name, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    output.push_str(", ");
+                }
+                output.push_str(&token_text(name));
+                output.push_str(": ");
+                write(output, value, 0);
+            }
+            output.push('}');
+        }
+        Expression::Field { record, field } => {
+            write(output, record, ATOMIC);
+            output.push('.');
+            output.push_str(&token_text(field));
+        }
+        Expression::Match { arguments, clauses } => write_match(output, arguments, clauses),
+    }
+
+    if parenthesize {
+        output.push(')');
+    }
+}
+
+fn write_statement(output: &mut String, statement: &Located<Statement>) {
+    match &statement.node {
+        Statement::Expression { expression } => write(output, expression, 0),
+        Statement::Variable { name, body } => {
+            output.push_str(&token_text(name));
+            output.push_str(" = ");
+            write(output, body, 0);
+        }
+        Statement::Definition {
+            name,
+            parameter,
+            body,
+        } => {
+            output.push_str(&token_text(name));
+            output.push(' ');
+            let body = write_parameters(output, parameter, body);
+            output.push_str(" = ");
+            write(output, body, 0);
+        }
+        _ => output.push_str("..."),
+    }
+}
+
+fn write_if(
+    output: &mut String,
+    branches: &[(Located<Expression>, Located<Expression>)],
+    otherwise: &Located<Expression>,
+) {
+    for (i, (condition, body)) in branches.iter().enumerate() {
+        output.push_str(if i == 0 { "if " } else { " elif " });
+        write(output, condition, 0);
+        output.push_str(" then ");
+        write(output, body, 0);
+    }
+    output.push_str(" else ");
+    write(output, otherwise, 0);
+}
+
+fn write_lambda(output: &mut String, parameter: &Located<Token>, body: &Located<Expression>) {
+    let body = write_parameters(output, parameter, body);
+    output.push_str(" $ ");
+    write(output, body, 0);
+}
+
+fn write_parameters<'a>(
+    output: &mut String,
+    parameter: &Located<Token>,
+    mut body: &'a Located<Expression>,
+) -> &'a Located<Expression> {
+    output.push_str(&token_text(parameter));
+    while let Expression::Lambda {
+        parameter,
+        body: inner,
+    } = &body.node
+    {
+        output.push(' ');
+        output.push_str(&token_text(parameter));
+        body = inner;
+    }
+    body
+}
+
+fn write_match(
+    output: &mut String,
+    arguments: &[Located<Token>],
+    clauses: &[(Vec<Located<Token>>, Located<Expression>)],
+) {
+    output.push_str("match");
+    for argument in arguments {
+        output.push(' ');
+        output.push_str(&token_text(argument));
+    }
+    for (patterns, body) in clauses {
+        output.push_str(" |");
+        for pattern in patterns {
+            output.push(' ');
+            output.push_str(&token_text(pattern));
+        }
+        output.push_str(" = ");
+        write(output, body, 0);
+    }
+}
+
+fn rank(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Unary { .. } => Precedence::Prefix as u8,
+        Expression::Binary { operator, .. } => Precedence::from(operator.node.kind) as u8,
+        Expression::Call { .. } => Precedence::Application as u8,
+        Expression::Literal { token } => match token.node.value {
+            TokenValue::Integer(integer) if integer < 0 => Precedence::Prefix as u8,
+            TokenValue::Float(float) if float.is_sign_negative() => Precedence::Prefix as u8,
+            _ => ATOMIC,
+        },
+        Expression::Block { .. }
+        | Expression::Identifier { .. }
+        | Expression::List { .. }
+        | Expression::Record { .. }
+        | Expression::Field { .. } => ATOMIC,
+        Expression::If { .. }
+        | Expression::Lambda { .. }
+        | Expression::Let { .. }
+        | Expression::Match { .. } => 0,
+    }
+}
+
+fn token_text(token: &Located<Token>) -> String {
+    match &token.node.value {
+        TokenValue::Identifier(name) | TokenValue::Use(name) => name.clone(),
+        TokenValue::Boolean(boolean) => boolean.to_string(),
+        TokenValue::Float(float) => float.to_string(),
+        TokenValue::Integer(integer) => integer.to_string(),
+        TokenValue::String(string) => format!("{string:?}"),
+        TokenValue::Unknown(character) => character.to_string(),
+        TokenValue::None if token.node.kind == TokenKind::Underscore => "_".to_string(),
+        TokenValue::None => "null".to_string(),
+    }
+}
+
+const fn symbol(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::StarStar => "**",
+        TokenKind::Slash => "/",
+        TokenKind::Percent => "%",
+        TokenKind::Ampersand => "&",
+        TokenKind::Caret => "^",
+        TokenKind::Pipe => "|",
+        TokenKind::PipeGreater => "|>",
+        TokenKind::Bang => "!",
+        TokenKind::BangEqual => "!=",
+        TokenKind::EqualEqual => "==",
+        TokenKind::Less => "<",
+        TokenKind::LessEqual => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEqual => ">=",
+        TokenKind::At => "@",
+        TokenKind::Colon => ":",
+        TokenKind::Hash => "#",
+        _ => "?",
+    }
+}
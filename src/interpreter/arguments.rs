@@ -0,0 +1,198 @@
+use super::Interpreter;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+struct OptionSpec {
+    name: String,
+    flag: String,
+    short: Option<char>,
+    help: String,
+    default: Option<Value>,
+}
+
+pub fn record(program: String, arguments: Vec<String>) -> Value {
+    let list = Value::List(arguments.iter().cloned().map(Value::String).collect());
+    let parse = Value::builtin(Purity::Effectful, move |interpreter, spec, location| {
+        let spec = interpreter.force(spec)?;
+        parse(interpreter, &program, &arguments, spec, location)
+    });
+    Value::Record(BTreeMap::from([
+        ("list".to_string(), list),
+        ("parse".to_string(), parse),
+    ]))
+}
+
+fn parse(
+    interpreter: &mut Interpreter,
+    program: &str,
+    arguments: &[String],
+    spec: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let options = options(spec, &location)?;
+    let mut values = BTreeMap::new();
+    let mut rest = vec![];
+
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        if argument == "--" {
+            rest.extend(arguments.by_ref().cloned().map(Value::String));
+            break;
+        }
+        if argument == "--help" || argument == "-h" {
+            for line in help(program, &options).lines() {
+                interpreter.write_line(line);
+            }
+            return Err(Error::new(ErrorKind::Exit(0), location));
+        }
+
+        let (option, inline) = if let Some(long) = argument.strip_prefix("--") {
+            let (flag, inline) = long.split_once('=').map_or((long, None), |(flag, value)| {
+                (flag, Some(value.to_string()))
+            });
+            (options.iter().find(|option| option.flag == flag), inline)
+        } else if let Some(short) = argument
+            .strip_prefix('-')
+            .filter(|s| s.chars().count() == 1)
+        {
+            let short = short.chars().next();
+            (options.iter().find(|option| option.short == short), None)
+        } else {
+            rest.push(Value::String(argument.clone()));
+            continue;
+        };
+        let Some(option) = option else {
+            return err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("Unknown option {argument}, see {program} --help"),
+            );
+        };
+
+        let value = if matches!(option.default, Some(Value::Boolean(_))) {
+            if inline.is_some() {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    format!("Option --{} does not take a value", option.flag),
+                );
+            }
+            Value::Boolean(true)
+        } else {
+            let Some(text) = inline.or_else(|| arguments.next().cloned()) else {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    format!("Option --{} expects a value", option.flag),
+                );
+            };
+            convert(option, &text, &location)?
+        };
+        values.insert(option.name.clone(), value);
+    }
+
+    for option in &options {
+        if values.contains_key(&option.name) {
+            continue;
+        }
+        let Some(default) = &option.default else {
+            return err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("Missing required option --{}", option.flag),
+            );
+        };
+        values.insert(option.name.clone(), default.clone());
+    }
+    values.insert("rest".to_string(), Value::List(rest));
+    Ok(Value::Record(values))
+}
+
+fn options(spec: Value, location: &Rc<Location>) -> Result<Vec<OptionSpec>> {
+    let invalid =
+        |message: String| Error::with_help(ErrorKind::InvalidArguments, location.clone(), message);
+    let Value::Record(fields) = spec else {
+        return Err(invalid(format!(
+            "args.parse expects a record of options, found {spec:?}"
+        )));
+    };
+
+    fields
+        .into_iter()
+        .map(|(name, settings)| {
+            let Value::Record(mut settings) = settings else {
+                return Err(invalid(format!(
+                    "Option {name} must be a record such as {{ help: \"...\", default: 1 }}"
+                )));
+            };
+            let short = match settings.remove("short") {
+                Some(Value::String(short)) if short.chars().count() == 1 => short.chars().next(),
+                None => None,
+                Some(_) => return Err(invalid(format!("Option {name} has an invalid short name"))),
+            };
+            let help = settings
+                .remove("help")
+                .map_or_else(String::new, |help| help.to_string());
+            Ok(OptionSpec {
+                flag: name.replace('_', "-"),
+                name,
+                short,
+                help,
+                default: settings.remove("default"),
+            })
+        })
+        .collect()
+}
+
+fn convert(option: &OptionSpec, text: &str, location: &Rc<Location>) -> Result<Value> {
+    let value = match option.default {
+        Some(Value::Integer(_)) => text.parse().ok().map(Value::Integer),
+        Some(Value::Float(_)) => text.parse().ok().map(Value::Float),
+        _ => Some(Value::String(text.to_string())),
+    };
+    value.ok_or_else(|| {
+        Error::with_help(
+            ErrorKind::InvalidArguments,
+            location.clone(),
+            format!("Option --{} expects a number, found {text}", option.flag),
+        )
+    })
+}
+
+fn help(program: &str, options: &[OptionSpec]) -> String {
+    let usage: Vec<_> = options
+        .iter()
+        .map(|option| {
+            let short = option
+                .short
+                .map_or_else(|| "    ".to_string(), |short| format!("-{short}, "));
+            let value = if matches!(option.default, Some(Value::Boolean(_))) {
+                ""
+            } else {
+                " <value>"
+            };
+            (format!("{short}--{}{value}", option.flag), option)
+        })
+        .collect();
+    let width = usage
+        .iter()
+        .map(|(usage, _)| usage.len())
+        .max()
+        .unwrap_or_default()
+        .max("-h, --help".len());
+
+    let mut output = format!("Usage: {program} [options] [arguments...]\n\nOptions:\n");
+    for (usage, option) in usage {
+        let default = match &option.default {
+            Some(Value::Boolean(_)) | None => String::new(),
+            Some(default) => format!(" (default: {default})"),
+        };
+        let _ = writeln!(output, "  {usage:<width$}  {}{default}", option.help);
+    }
+    let _ = writeln!(output, "  {:<width$}  Show this help", "-h, --help");
+    output
+}
@@ -1,8 +1,317 @@
-use crate::error::Result;
-use crate::model::{Location, Value};
-use std::rc::Rc;
+use super::{Interpreter, diff};
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Tag, Value, ValueKey};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-pub fn println(arg: Value, _: Rc<Location>) -> Result<Value> {
-    println!("{arg}");
+pub fn println(interpreter: &mut Interpreter, arg: Value, _: Rc<Location>) -> Result<Value> {
+    let arg = interpreter.force(arg)?;
+    interpreter.write_line(&arg.to_string());
     Ok(arg)
 }
+
+pub fn exit(interpreter: &mut Interpreter, code: Value, location: Rc<Location>) -> Result<Value> {
+    match interpreter.force(code)? {
+        Value::Integer(code) => match u8::try_from(code) {
+            Ok(code) => Err(Error::new(ErrorKind::Exit(code), location)),
+            Err(_) => err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("exit expects a code between 0 and 255, found {code}"),
+            ),
+        },
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("exit expects an integer, found {other:?}"),
+        ),
+    }
+}
+
+pub fn on_exit(
+    interpreter: &mut Interpreter,
+    hook: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let hook = interpreter.force(hook)?;
+    if !hook.is_function() {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "on_exit expects a function taking the exit code",
+        );
+    }
+    interpreter.on_exit(hook, location);
+    Ok(Value::None)
+}
+
+pub fn on_interrupt(
+    interpreter: &mut Interpreter,
+    handler: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let handler = interpreter.force(handler)?;
+    if !handler.is_function() {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "on_interrupt expects a function",
+        );
+    }
+    interpreter.on_interrupt(handler);
+    Ok(Value::None)
+}
+
+pub fn assert_eq(interpreter: &mut Interpreter, actual: Value, _: Rc<Location>) -> Result<Value> {
+    let actual = interpreter.force(actual)?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, expected, location| {
+            let expected = interpreter.force(expected)?;
+            match (
+                ValueKey::from_value(&actual),
+                ValueKey::from_value(&expected),
+            ) {
+                (Some(a), Some(e)) if a == e => Ok(Value::Boolean(true)),
+                (Some(_), Some(_)) => err!(
+                    ErrorKind::AssertionFailed,
+                    location,
+                    diff::describe(&expected, &actual),
+                ),
+                _ => err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    format!("assert_eq cannot compare {actual:?} with {expected:?}"),
+                ),
+            }
+        },
+    ))
+}
+
+pub fn length(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let length = match interpreter.force(value)? {
+        Value::List(elements) => elements.len(),
+        Value::String(string) => string.chars().count(),
+        other => {
+            return err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("length expects a list or string, found {other:?}"),
+            );
+        }
+    };
+    Ok(Value::Integer(length as i128))
+}
+
+pub fn index(interpreter: &mut Interpreter, list: Value, location: Rc<Location>) -> Result<Value> {
+    let Value::List(elements) = interpreter.force(list)? else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "index expects a list as its first argument",
+        );
+    };
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, position, location| match interpreter.force(position)? {
+            Value::Integer(position) => usize::try_from(position)
+                .ok()
+                .and_then(|position| elements.get(position))
+                .cloned()
+                .ok_or_else(|| {
+                    Error::with_help(
+                        ErrorKind::InvalidArguments,
+                        location,
+                        format!(
+                            "Index {position} is out of bounds for a list of length {}",
+                            elements.len()
+                        ),
+                    )
+                }),
+            other => err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("index expects an integer position, found {other:?}"),
+            ),
+        },
+    ))
+}
+
+pub fn string_argument(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+    name: &str,
+) -> Result<String> {
+    match interpreter.force(value)? {
+        Value::String(string) => Ok(string),
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects a string, found {other:?}"),
+        ),
+    }
+}
+
+pub fn memoize(
+    interpreter: &mut Interpreter,
+    function: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    match interpreter.force(function)? {
+        function if function.purity() == Purity::Effectful => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "Effectful builtins cannot be memoized",
+        ),
+        function if function.is_function() => Ok(memoized(function)),
+        _ => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "memoize expects a function",
+        ),
+    }
+}
+
+fn memoized(function: Value) -> Value {
+    let cache: RefCell<HashMap<ValueKey, Value>> = RefCell::default();
+    Value::builtin(function.purity(), move |interpreter, argument, location| {
+        let argument = interpreter.force(argument)?;
+        let Some(key) = ValueKey::from_value(&argument) else {
+            return err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("Cannot memoize on the unhashable argument {argument:?}"),
+            );
+        };
+        if let Some(value) = cache.borrow().get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = interpreter.apply(function.clone(), argument, location.clone())?;
+        let value = match interpreter.force(value)? {
+            function if function.purity() == Purity::Effectful => {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    "Effectful builtins cannot be memoized",
+                );
+            }
+            function if function.is_function() => memoized(function),
+            other => other,
+        };
+        cache.borrow_mut().insert(key, value.clone());
+        Ok(value)
+    })
+}
+
+pub fn tag(tag: Tag) -> impl Fn(&mut Interpreter, Value, Rc<Location>) -> Result<Value> {
+    move |interpreter, value, _| {
+        Ok(Value::Tagged {
+            tag: tag.clone(),
+            value: Box::new(interpreter.force(value)?),
+        })
+    }
+}
+
+pub fn unwrap(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    match interpreter.force(value)? {
+        Value::Tagged { value, .. } => Ok(*value),
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("unwrap expects a tagged value, found {other:?}"),
+        ),
+    }
+}
+
+pub fn r#loop(interpreter: &mut Interpreter, state: Value, _: Rc<Location>) -> Result<Value> {
+    let state = interpreter.force(state)?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, step, location| {
+            let step = interpreter.force(step)?;
+            let mut state = state.clone();
+            loop {
+                let next = interpreter.apply(step.clone(), state, location.clone())?;
+                match interpreter.force(next)? {
+                    Value::Tagged { tag, value } if tag == Tag::CONTINUE => state = *value,
+                    Value::Tagged { tag, value } if tag == Tag::DONE => return Ok(*value),
+                    other => {
+                        return err!(
+                            ErrorKind::InvalidArguments,
+                            location,
+                            format!(
+                                "loop expects Continue or Done from its step function, found {other:?}"
+                            ),
+                        );
+                    }
+                }
+            }
+        },
+    ))
+}
+
+pub fn gensym(
+    interpreter: &mut Interpreter,
+    prefix: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let prefix = string_argument(interpreter, prefix, location, "gensym")?;
+    Ok(Value::String(interpreter.gensym(&prefix)))
+}
+
+pub fn with_context(
+    interpreter: &mut Interpreter,
+    message: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let message = string_argument(interpreter, message, location, "with_context")?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, function, location| {
+            let function = interpreter.force(function)?;
+            if !function.is_function() {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    "with_context expects a function as its second argument",
+                );
+            }
+            interpreter
+                .apply(function, Value::None, location.clone())
+                .and_then(|value| interpreter.force(value))
+                .map_err(|error| error.context(message.clone(), location))
+        },
+    ))
+}
+
+pub fn same(interpreter: &mut Interpreter, left: Value, _: Rc<Location>) -> Result<Value> {
+    let left = interpreter.force(left)?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, right, location| match (&left, interpreter.force(right)?) {
+            (Value::Function { id: l, .. }, Value::Function { id: r, .. }) => {
+                Ok(Value::Boolean(*l == r))
+            }
+            (
+                Value::BuiltinFunction { function: l, .. },
+                Value::BuiltinFunction { function: r, .. },
+            ) => Ok(Value::Boolean(Rc::ptr_eq(l, &r))),
+            (left, right) if left.is_function() && right.is_function() => Ok(Value::Boolean(false)),
+            _ => err!(
+                ErrorKind::InvalidArguments,
+                location,
+                "same compares functions by identity, use == to compare values",
+            ),
+        },
+    ))
+}
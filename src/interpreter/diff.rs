@@ -0,0 +1,140 @@
+use super::Interpreter;
+use crate::error::Result;
+use crate::model::{Location, Purity, Value, ValueKey};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+pub struct Difference {
+    pub path: String,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+pub fn diff(interpreter: &mut Interpreter, left: Value, _: Rc<Location>) -> Result<Value> {
+    let left = interpreter.force(left)?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, right, _| {
+            let right = interpreter.force(right)?;
+            Ok(Value::List(
+                differences(&left, &right)
+                    .into_iter()
+                    .map(|difference| {
+                        Value::Record(BTreeMap::from([
+                            ("path".to_string(), Value::String(difference.path)),
+                            ("left".to_string(), difference.left.unwrap_or(Value::None)),
+                            ("right".to_string(), difference.right.unwrap_or(Value::None)),
+                        ]))
+                    })
+                    .collect(),
+            ))
+        },
+    ))
+}
+
+pub fn differences(left: &Value, right: &Value) -> Vec<Difference> {
+    let mut differences = vec![];
+    compare(".".to_string(), left, right, &mut differences);
+    differences
+}
+
+pub fn describe(expected: &Value, actual: &Value) -> String {
+    let mut description = format!("Expected {expected}, found {actual}");
+    let differences = differences(expected, actual);
+    if differences.iter().any(|difference| difference.path != ".") {
+        for difference in differences {
+            let _ = match (difference.left, difference.right) {
+                (Some(expected), Some(actual)) => write!(
+                    description,
+                    "\n  at {}: expected {expected}, found {actual}",
+                    difference.path
+                ),
+                (Some(expected), None) => {
+                    write!(
+                        description,
+                        "\n  at {}: missing {expected}",
+                        difference.path
+                    )
+                }
+                (None, Some(actual)) => {
+                    write!(
+                        description,
+                        "\n  at {}: unexpected {actual}",
+                        difference.path
+                    )
+                }
+                (None, None) => Ok(()),
+            };
+        }
+    }
+    description
+}
+
+fn compare(path: String, left: &Value, right: &Value, differences: &mut Vec<Difference>) {
+    match (left, right) {
+        (Value::List(l), Value::List(r)) => {
+            for i in 0..l.len().max(r.len()) {
+                let path = child(&path, &format!("[{i}]"));
+                match (l.get(i), r.get(i)) {
+                    (Some(l), Some(r)) => compare(path, l, r, differences),
+                    (l, r) => differences.push(Difference {
+                        path,
+                        left: l.cloned(),
+                        right: r.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Record(l), Value::Record(r)) => {
+            let mut names: Vec<_> = l.keys().chain(r.keys()).collect();
+            names.sort();
+            names.dedup();
+            for name in names {
+                let path = child(&path, &format!(".{name}"));
+                match (l.get(name), r.get(name)) {
+                    (Some(l), Some(r)) => compare(path, l, r, differences),
+                    (l, r) => differences.push(Difference {
+                        path,
+                        left: l.cloned(),
+                        right: r.cloned(),
+                    }),
+                }
+            }
+        }
+        (
+            Value::Tagged {
+                tag: left_tag,
+                value: l,
+            },
+            Value::Tagged {
+                tag: right_tag,
+                value: r,
+            },
+        ) if left_tag == right_tag => compare(path, l, r, differences),
+        (l, r) => {
+            let equal = ValueKey::from_value(l)
+                .zip(ValueKey::from_value(r))
+                .is_some_and(|(l, r)| l == r);
+            if !equal {
+                differences.push(Difference {
+                    path,
+                    left: Some(l.clone()),
+                    right: Some(r.clone()),
+                });
+            }
+        }
+    }
+}
+
+fn child(path: &str, segment: &str) -> String {
+    if path == "." {
+        if segment.starts_with('.') {
+            segment.to_string()
+        } else {
+            format!(".{segment}")
+        }
+    } else {
+        format!("{path}{segment}")
+    }
+}
@@ -0,0 +1,120 @@
+use super::Interpreter;
+use super::builtins::string_argument;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Value};
+use std::fmt::Write;
+use std::rc::Rc;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64_encode(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let bytes = string_argument(interpreter, value, location, "base64_encode")?.into_bytes();
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    Ok(Value::String(encoded))
+}
+
+pub fn base64_decode(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let encoded = string_argument(interpreter, value, location.clone(), "base64_decode")?;
+    let invalid = || {
+        Error::with_help(
+            ErrorKind::InvalidArguments,
+            location.clone(),
+            "base64_decode expects valid base64",
+        )
+    };
+
+    let trimmed = encoded.trim_end_matches('=');
+    if encoded.len() % 4 != 0 || encoded.len() - trimmed.len() > 2 {
+        return Err(invalid());
+    }
+    let sextets = trimmed
+        .bytes()
+        .map(|c| {
+            BASE64_ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .and_then(|i| u32::try_from(i).ok())
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut bytes = vec![];
+    for chunk in sextets.chunks(4) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &sextet)| group | sextet << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push(group.to_be_bytes()[i + 1]);
+        }
+    }
+    into_string(bytes, location, "base64_decode")
+}
+
+pub fn hex_encode(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let string = string_argument(interpreter, value, location, "hex_encode")?;
+    Ok(Value::String(string.bytes().fold(
+        String::new(),
+        |mut output, byte| {
+            let _ = write!(output, "{byte:02x}");
+            output
+        },
+    )))
+}
+
+pub fn hex_decode(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let encoded = string_argument(interpreter, value, location.clone(), "hex_decode")?;
+    if encoded.len() % 2 != 0 || !encoded.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "hex_decode expects an even number of hexadecimal digits",
+        );
+    }
+    let bytes = (0..encoded.len())
+        .step_by(2)
+        .filter_map(|i| u8::from_str_radix(&encoded[i..i + 2], 16).ok())
+        .collect();
+    into_string(bytes, location, "hex_decode")
+}
+
+fn into_string(bytes: Vec<u8>, location: Rc<Location>, name: &str) -> Result<Value> {
+    String::from_utf8(bytes).map(Value::String).map_err(|_| {
+        Error::with_help(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} produced bytes that are not valid UTF-8"),
+        )
+    })
+}
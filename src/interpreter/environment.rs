@@ -1,10 +1,20 @@
-use super::builtins::println;
-use crate::model::Value;
+use super::Interpreter;
+use super::builtins::{
+    assert_eq, exit, gensym, index, length, r#loop, memoize, on_exit, on_interrupt, println, same,
+    tag, unwrap, with_context,
+};
+use super::{
+    arguments, diff, encoding, hash, lists, matrix, numeric, prompt, style, template, transfer,
+    variables,
+};
+use crate::error::Result;
+use crate::model::{Located, Location, Purity, Tag, Token, Value};
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
 #[derive(Debug)]
 pub struct Environment {
     identifiers: HashMap<String, Value>,
+    signatures: HashMap<String, Vec<Located<Token>>>,
     parent: Option<Rc<RefCell<Self>>>,
 }
 
@@ -12,6 +22,7 @@ impl Environment {
     pub fn new() -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self {
             identifiers: HashMap::new(),
+            signatures: HashMap::new(),
             parent: None,
         }))
     }
@@ -19,17 +30,77 @@ impl Environment {
     pub fn with_parent(parent: Rc<RefCell<Self>>) -> Rc<RefCell<Self>> {
         Rc::new(RefCell::new(Self {
             identifiers: HashMap::new(),
+            signatures: HashMap::new(),
             parent: Some(parent),
         }))
     }
 
     pub fn add_builtins(&mut self) {
+        self.set_builtin("println", Purity::Effectful, println);
+        self.set_builtin("exit", Purity::Effectful, exit);
+        self.set_builtin("on_exit", Purity::Effectful, on_exit);
+        self.set_builtin("on_interrupt", Purity::Effectful, on_interrupt);
+        self.set_builtin("prompt", Purity::Effectful, prompt::prompt);
+        self.set_builtin("confirm", Purity::Effectful, prompt::confirm);
+        self.set_builtin("select", Purity::Effectful, prompt::select);
+        self.set_builtin("password", Purity::Effectful, prompt::password);
+        self.set_builtin("memoize", Purity::Pure, memoize);
+        self.set_builtin("loop", Purity::Pure, r#loop);
+        self.set_builtin("Continue", Purity::Pure, tag(Tag::CONTINUE));
+        self.set_builtin("Done", Purity::Pure, tag(Tag::DONE));
+        self.set_builtin("same", Purity::Pure, same);
+        self.set_builtin("gensym", Purity::Effectful, gensym);
+        self.set_builtin("with_context", Purity::Pure, with_context);
+        self.set_builtin("unwrap", Purity::Pure, unwrap);
+        self.set_builtin("assert_eq", Purity::Pure, assert_eq);
+        self.set_builtin("diff", Purity::Pure, diff::diff);
+        self.set_builtin("length", Purity::Pure, length);
+        self.set_builtin("index", Purity::Pure, index);
+        self.set_builtin("group_by", Purity::Pure, lists::group_by);
+        self.set_builtin("chunks", Purity::Pure, lists::chunks);
+        self.set_builtin("windows", Purity::Pure, lists::windows);
+        self.set_builtin("frequencies", Purity::Pure, lists::frequencies);
+        self.set_builtin("head", Purity::Pure, lists::head);
+        self.set_builtin("tail", Purity::Pure, lists::tail);
+        self.set_builtin("isEmpty", Purity::Pure, lists::is_empty);
+        self.set_builtin("isolate", Purity::Effectful, transfer::isolate);
+        self.set_builtin("hash_sha256", Purity::Pure, hash::sha256);
+        self.set_builtin("hash_md5", Purity::Pure, hash::md5);
+        self.set_builtin("hash_value", Purity::Pure, hash::value);
+        self.set_builtin("base64_encode", Purity::Pure, encoding::base64_encode);
+        self.set_builtin("base64_decode", Purity::Pure, encoding::base64_decode);
+        self.set_builtin("hex_encode", Purity::Pure, encoding::hex_encode);
+        self.set_builtin("hex_decode", Purity::Pure, encoding::hex_decode);
         self.set(
-            "println".to_string(),
-            Value::BuiltinFunction {
-                function: Rc::new(println),
-            },
+            "args".to_string(),
+            arguments::record("icypeas".to_string(), vec![]),
         );
+        self.set("style".to_string(), style::record());
+        self.set("env".to_string(), variables::record());
+        self.set_builtin("toFloat", Purity::Pure, numeric::to_float);
+        self.set_builtin("truncate", Purity::Pure, numeric::truncate);
+        self.set_builtin("round", Purity::Pure, numeric::round);
+        self.set_builtin("floorInt", Purity::Pure, numeric::floor_int);
+        self.set_builtin("sum", Purity::Pure, numeric::sum);
+        self.set_builtin("product", Purity::Pure, numeric::product);
+        self.set_builtin("minimum", Purity::Pure, numeric::minimum);
+        self.set_builtin("maximum", Purity::Pure, numeric::maximum);
+        self.set_builtin("average", Purity::Pure, numeric::average);
+        self.set_builtin("clamp", Purity::Pure, numeric::clamp);
+        self.set_builtin("render", Purity::Pure, template::render);
+        self.set_builtin("matrix_identity", Purity::Pure, matrix::identity);
+        self.set_builtin("matrix_transpose", Purity::Pure, matrix::transpose);
+        self.set_builtin("matrix_multiply", Purity::Pure, matrix::multiply);
+        self.set_builtin("matrix_determinant", Purity::Pure, matrix::determinant);
+    }
+
+    fn set_builtin(
+        &mut self,
+        name: &str,
+        purity: Purity,
+        function: impl Fn(&mut Interpreter, Value, Rc<Location>) -> Result<Value> + 'static,
+    ) {
+        self.set(name.to_string(), Value::builtin(purity, function));
     }
 
     pub fn get(&self, key: &str) -> Option<Value> {
@@ -40,7 +111,23 @@ impl Environment {
         })
     }
 
+    pub fn parent(&self) -> Option<Rc<RefCell<Self>>> {
+        self.parent.clone()
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item = (&String, &Value)> {
+        self.identifiers.iter()
+    }
+
     pub fn set(&mut self, key: String, value: Value) {
         self.identifiers.insert(key, value);
     }
+
+    pub fn declare(&mut self, name: String, types: Vec<Located<Token>>) {
+        self.signatures.insert(name, types);
+    }
+
+    pub fn signature(&self, name: &str) -> Option<&[Located<Token>]> {
+        self.signatures.get(name).map(Vec::as_slice)
+    }
 }
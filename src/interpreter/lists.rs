@@ -0,0 +1,229 @@
+use super::Interpreter;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Value, ValueKey};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+pub fn group_by(
+    interpreter: &mut Interpreter,
+    key: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let key = interpreter.force(key)?;
+    if !key.is_function() {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "group_by expects a key function",
+        );
+    }
+
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, list, location| {
+            let elements = list_argument(interpreter, list, location.clone(), "group_by")?;
+            let mut groups: Vec<(Value, Vec<Value>)> = vec![];
+            let mut positions = HashMap::new();
+            for element in elements {
+                let group = interpreter.apply(key.clone(), element.clone(), location.clone())?;
+                let group = interpreter.force(group)?;
+                let position = *positions
+                    .entry(hashable(&group, &location)?)
+                    .or_insert_with(|| {
+                        groups.push((group, vec![]));
+                        groups.len() - 1
+                    });
+                groups[position].1.push(element);
+            }
+            Ok(Value::List(
+                groups
+                    .into_iter()
+                    .map(|(key, values)| entry("key", key, "values", Value::List(values)))
+                    .collect(),
+            ))
+        },
+    ))
+}
+
+pub fn chunks(interpreter: &mut Interpreter, size: Value, location: Rc<Location>) -> Result<Value> {
+    let size = size_argument(interpreter, size, location, "chunks")?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, list, location| {
+            let elements = list_argument(interpreter, list, location, "chunks")?;
+            Ok(Value::List(
+                elements
+                    .chunks(size)
+                    .map(|chunk| Value::List(chunk.to_vec()))
+                    .collect(),
+            ))
+        },
+    ))
+}
+
+pub fn windows(
+    interpreter: &mut Interpreter,
+    size: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let size = size_argument(interpreter, size, location, "windows")?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, list, location| {
+            let elements = list_argument(interpreter, list, location, "windows")?;
+            Ok(Value::List(
+                elements
+                    .windows(size)
+                    .map(|window| Value::List(window.to_vec()))
+                    .collect(),
+            ))
+        },
+    ))
+}
+
+pub fn frequencies(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let elements = list_argument(interpreter, list, location.clone(), "frequencies")?;
+    let keys = elements
+        .iter()
+        .map(ValueKey::from_value)
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| {
+            Error::with_help(
+                ErrorKind::InvalidArguments,
+                location,
+                "frequencies cannot count functions",
+            )
+        })?;
+
+    let mut counts: Vec<(Value, i128)> = vec![];
+    let mut positions = HashMap::new();
+    for (element, key) in elements.into_iter().zip(keys) {
+        let position = *positions.entry(key).or_insert_with(|| {
+            counts.push((element, 0));
+            counts.len() - 1
+        });
+        counts[position].1 += 1;
+    }
+    Ok(Value::List(
+        counts
+            .into_iter()
+            .map(|(value, count)| entry("value", value, "count", Value::Integer(count)))
+            .collect(),
+    ))
+}
+
+pub fn head(interpreter: &mut Interpreter, list: Value, location: Rc<Location>) -> Result<Value> {
+    let mut elements = non_empty(interpreter, list, location, "head")?;
+    Ok(elements.swap_remove(0))
+}
+
+pub fn tail(interpreter: &mut Interpreter, list: Value, location: Rc<Location>) -> Result<Value> {
+    let mut elements = non_empty(interpreter, list, location, "tail")?;
+    elements.remove(0);
+    Ok(Value::List(elements))
+}
+
+pub fn is_empty(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    match interpreter.force(list)? {
+        Value::List(elements) => Ok(Value::Boolean(elements.is_empty())),
+        _ => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "isEmpty expects a list"
+        ),
+    }
+}
+
+fn non_empty(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+    name: &str,
+) -> Result<Vec<Value>> {
+    match interpreter.force(list)? {
+        Value::List(elements) if !elements.is_empty() => Ok(elements),
+        Value::List(_) => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects a non-empty list"),
+        ),
+        _ => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects a list"),
+        ),
+    }
+}
+
+fn entry(key_name: &str, key: Value, value_name: &str, value: Value) -> Value {
+    Value::Record(BTreeMap::from([
+        (key_name.to_string(), key),
+        (value_name.to_string(), value),
+    ]))
+}
+
+fn hashable(value: &Value, location: &Rc<Location>) -> Result<ValueKey> {
+    ValueKey::from_value(value).map_or_else(
+        || {
+            err!(
+                ErrorKind::InvalidArguments,
+                location.clone(),
+                format!("group_by cannot group by the function {value:?}"),
+            )
+        },
+        Ok,
+    )
+}
+
+fn list_argument(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+    name: &str,
+) -> Result<Vec<Value>> {
+    let Value::List(elements) = interpreter.force(list)? else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects a list"),
+        );
+    };
+    elements
+        .into_iter()
+        .map(|element| interpreter.force(element))
+        .collect()
+}
+
+fn size_argument(
+    interpreter: &mut Interpreter,
+    size: Value,
+    location: Rc<Location>,
+    name: &str,
+) -> Result<usize> {
+    match interpreter.force(size)? {
+        Value::Integer(size) if size > 0 => usize::try_from(size).map_or_else(
+            |_| {
+                err!(
+                    ErrorKind::Overflow,
+                    location,
+                    format!("{name} size {size} is too large"),
+                )
+            },
+            Ok,
+        ),
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects a positive size, found {other:?}"),
+        ),
+    }
+}
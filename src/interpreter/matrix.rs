@@ -0,0 +1,303 @@
+use super::Interpreter;
+use super::numeric::promote;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Value};
+use std::rc::Rc;
+
+trait Scalar: Copy + PartialEq {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn add(self, other: Self) -> Option<Self>;
+    fn sub(self, other: Self) -> Option<Self>;
+    fn mul(self, other: Self) -> Option<Self>;
+    fn div(self, other: Self) -> Option<Self>;
+    fn into_value(self) -> Value;
+}
+
+impl Scalar for i128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn add(self, other: Self) -> Option<Self> {
+        self.checked_add(other)
+    }
+
+    fn sub(self, other: Self) -> Option<Self> {
+        self.checked_sub(other)
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        self.checked_mul(other)
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        self.checked_div(other)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Integer(self)
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+
+    fn add(self, other: Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn sub(self, other: Self) -> Option<Self> {
+        Some(self - other)
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn div(self, other: Self) -> Option<Self> {
+        Some(self / other)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Float(self)
+    }
+}
+
+enum Matrix {
+    Integer(Vec<Vec<i128>>),
+    Float(Vec<Vec<f64>>),
+}
+
+impl Matrix {
+    fn from_value(value: Value, location: Rc<Location>) -> Result<Self> {
+        let invalid = move || {
+            Error::with_help(
+                ErrorKind::InvalidArguments,
+                location.clone(),
+                "Expected a matrix given as a list of equally long lists of numbers",
+            )
+        };
+
+        let Value::List(rows) = value else {
+            return Err(invalid());
+        };
+        let rows = rows
+            .into_iter()
+            .map(|row| match row {
+                Value::List(row) => Ok(row),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if rows.windows(2).any(|pair| pair[0].len() != pair[1].len()) {
+            return Err(invalid());
+        }
+
+        let cells = || rows.iter().flatten();
+        if cells().all(|cell| matches!(cell, Value::Integer(_))) {
+            Ok(Self::Integer(map_cells(&rows, |cell| match cell {
+                Value::Integer(i) => *i,
+                _ => unreachable!(),
+            })))
+        } else if cells().all(|cell| matches!(cell, Value::Integer(_) | Value::Float(_))) {
+            Ok(Self::Float(map_cells(&rows, |cell| match cell {
+                Value::Integer(i) => promote(*i),
+                Value::Float(f) => *f,
+                _ => unreachable!(),
+            })))
+        } else {
+            Err(invalid())
+        }
+    }
+}
+
+fn map_cells<T>(rows: &[Vec<Value>], f: impl Fn(&Value) -> T) -> Vec<Vec<T>> {
+    rows.iter()
+        .map(|row| row.iter().map(&f).collect())
+        .collect()
+}
+
+fn into_value<T: Scalar>(rows: Vec<Vec<T>>) -> Value {
+    Value::List(
+        rows.into_iter()
+            .map(|row| Value::List(row.into_iter().map(Scalar::into_value).collect()))
+            .collect(),
+    )
+}
+
+fn overflow(location: &Rc<Location>) -> Error {
+    Error::new(ErrorKind::Overflow, location.clone())
+}
+
+pub fn identity(
+    interpreter: &mut Interpreter,
+    size: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let Value::Integer(size) = interpreter.force(size)? else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "matrix_identity expects an integer size",
+        );
+    };
+    let Ok(size) = usize::try_from(size) else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "matrix_identity expects a non-negative size",
+        );
+    };
+
+    Ok(into_value(
+        (0..size)
+            .map(|i| (0..size).map(|j| i128::from(i == j)).collect())
+            .collect(),
+    ))
+}
+
+pub fn transpose(
+    interpreter: &mut Interpreter,
+    matrix: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let matrix = interpreter.force(matrix)?;
+    Ok(match Matrix::from_value(matrix, location)? {
+        Matrix::Integer(rows) => into_value(transposed(&rows)),
+        Matrix::Float(rows) => into_value(transposed(&rows)),
+    })
+}
+
+fn transposed<T: Scalar>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    let columns = rows.first().map_or(0, Vec::len);
+    (0..columns)
+        .map(|j| rows.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+pub fn multiply(
+    interpreter: &mut Interpreter,
+    left: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let left = Matrix::from_value(interpreter.force(left)?, location)?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, right, location| {
+            let right = Matrix::from_value(interpreter.force(right)?, location.clone())?;
+            match (&left, right) {
+                (Matrix::Integer(l), Matrix::Integer(r)) => {
+                    product(l, &r, &location).map(into_value)
+                }
+                (Matrix::Integer(l), Matrix::Float(r)) => {
+                    product(&to_float(l), &r, &location).map(into_value)
+                }
+                (Matrix::Float(l), Matrix::Integer(r)) => {
+                    product(l, &to_float(&r), &location).map(into_value)
+                }
+                (Matrix::Float(l), Matrix::Float(r)) => product(l, &r, &location).map(into_value),
+            }
+        },
+    ))
+}
+
+fn to_float(rows: &[Vec<i128>]) -> Vec<Vec<f64>> {
+    rows.iter()
+        .map(|row| row.iter().map(|&cell| promote(cell)).collect())
+        .collect()
+}
+
+fn product<T: Scalar>(
+    left: &[Vec<T>],
+    right: &[Vec<T>],
+    location: &Rc<Location>,
+) -> Result<Vec<Vec<T>>> {
+    let inner = left.first().map_or(0, Vec::len);
+    if inner != right.len() {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location.clone(),
+            format!(
+                "Cannot multiply a matrix with {inner} columns by a matrix with {} rows",
+                right.len()
+            ),
+        );
+    }
+
+    let columns = right.first().map_or(0, Vec::len);
+    left.iter()
+        .map(|row| {
+            (0..columns)
+                .map(|j| {
+                    (0..inner).try_fold(T::ZERO, |sum, k| {
+                        row[k]
+                            .mul(right[k][j])
+                            .and_then(|term| sum.add(term))
+                            .ok_or_else(|| overflow(location))
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn determinant(
+    interpreter: &mut Interpreter,
+    matrix: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let matrix = interpreter.force(matrix)?;
+    match Matrix::from_value(matrix, location.clone())? {
+        Matrix::Integer(rows) => bareiss(rows, location).map(Value::Integer),
+        Matrix::Float(rows) => bareiss(rows, location).map(Value::Float),
+    }
+}
+
+fn bareiss<T: Scalar>(mut rows: Vec<Vec<T>>, location: Rc<Location>) -> Result<T> {
+    let size = rows.len();
+    if rows.iter().any(|row| row.len() != size) {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "matrix_determinant expects a square matrix",
+        );
+    }
+    if size == 0 {
+        return Ok(T::ONE);
+    }
+
+    let mut negate = false;
+    let mut previous = T::ONE;
+    for k in 0..size - 1 {
+        if rows[k][k] == T::ZERO {
+            match (k + 1..size).find(|&i| rows[i][k] != T::ZERO) {
+                Some(i) => {
+                    rows.swap(k, i);
+                    negate = !negate;
+                }
+                None => return Ok(T::ZERO),
+            }
+        }
+        for i in k + 1..size {
+            for j in k + 1..size {
+                let kept = rows[i][j].mul(rows[k][k]);
+                let removed = rows[i][k].mul(rows[k][j]);
+                rows[i][j] = kept
+                    .zip(removed)
+                    .and_then(|(kept, removed)| kept.sub(removed))
+                    .and_then(|difference| difference.div(previous))
+                    .ok_or_else(|| overflow(&location))?;
+            }
+        }
+        previous = rows[k][k];
+    }
+
+    let determinant = rows[size - 1][size - 1];
+    if negate {
+        T::ZERO.sub(determinant).ok_or_else(|| overflow(&location))
+    } else {
+        Ok(determinant)
+    }
+}
@@ -1,49 +1,368 @@
+pub mod arguments;
 pub mod builtins;
+pub mod cancel;
+pub mod diff;
+pub mod encoding;
 pub mod environment;
+pub mod hash;
+pub mod lists;
+pub mod matrix;
+pub mod numeric;
+pub mod prompt;
+pub mod signal;
+pub mod style;
+pub mod template;
+pub mod transfer;
+pub mod variables;
 
+pub use cancel::CancelHandle;
 pub use environment::Environment;
 
+use builtins::tag;
+
+use crate::checker;
+use crate::engine::{ParseCache, TestCase, Timing};
 use crate::err;
 use crate::error::{Error, ErrorKind, Result};
-use crate::lexer::Lexer;
-use crate::model::{Expression, Located, Statement, Token, TokenKind, TokenValue, Value};
-use crate::parser::Parser;
+use crate::model::{
+    Expression, Located, Location, Purity, Statement, Tag, Token, TokenKind, TokenValue, Value,
+    ValueKey,
+};
+use crate::resolver;
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryInto;
-use std::path::PathBuf;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Evaluation {
+    #[default]
+    Lazy,
+    Strict,
+}
 
 pub struct Interpreter {
     environment: Rc<RefCell<Environment>>,
+    globals: Rc<RefCell<Environment>>,
     current_file: Option<PathBuf>,
+    cache: Rc<ParseCache>,
+    deadline: Option<Instant>,
+    steps: usize,
+    output: Option<String>,
+    timings: Option<Vec<Timing>>,
+    tests: Option<Vec<(String, Located<Expression>)>>,
+    interactive: bool,
+    color: bool,
+    exit_hooks: Vec<(Value, Rc<Location>)>,
+    interrupt_handler: Option<Value>,
+    cancel: CancelHandle,
+    strict: bool,
+    evaluation: Evaluation,
+    symbols: usize,
+    forcing: Vec<Rc<Location>>,
+    lazy_effects: Option<Vec<Rc<Location>>>,
+    loaded_variables: HashMap<String, String>,
+    modules: HashMap<Option<PathBuf>, String>,
+    defined_in: HashMap<String, Option<PathBuf>>,
 }
 
 impl Interpreter {
-    pub const fn new(environment: Rc<RefCell<Environment>>) -> Self {
+    pub fn with_file(
+        environment: Rc<RefCell<Environment>>,
+        file: Option<PathBuf>,
+        cache: Rc<ParseCache>,
+    ) -> Self {
         Self {
+            globals: environment.clone(),
             environment,
-            current_file: None,
+            current_file: file,
+            cache,
+            deadline: None,
+            steps: 0,
+            output: None,
+            timings: None,
+            tests: None,
+            interactive: true,
+            color: true,
+            exit_hooks: vec![],
+            interrupt_handler: None,
+            cancel: CancelHandle::default(),
+            strict: false,
+            evaluation: Evaluation::Lazy,
+            symbols: 0,
+            forcing: vec![],
+            lazy_effects: None,
+            loaded_variables: HashMap::new(),
+            modules: HashMap::new(),
+            defined_in: HashMap::new(),
         }
     }
 
-    pub const fn with_file(environment: Rc<RefCell<Environment>>, file: Option<PathBuf>) -> Self {
-        Self {
-            environment,
-            current_file: file,
+    pub fn globals(&self) -> Rc<RefCell<Environment>> {
+        self.globals.clone()
+    }
+
+    pub fn current_file(&self) -> Option<&Path> {
+        self.current_file.as_deref()
+    }
+
+    pub fn add_module(&mut self, file: Option<PathBuf>, source: &str) {
+        self.modules.insert(file, source.to_string());
+    }
+
+    pub fn module_of(&self, name: &str) -> Option<(Option<&Path>, &str)> {
+        let file = self.defined_in.get(name)?;
+        Some((file.as_deref(), self.modules.get(file)?))
+    }
+
+    pub fn loaded_variable(&self, name: &str) -> Option<String> {
+        self.loaded_variables.get(name).cloned()
+    }
+
+    pub fn load_variables(&mut self, variables: impl IntoIterator<Item = (String, String)>) {
+        self.loaded_variables.extend(variables);
+    }
+
+    pub const fn set_deadline(&mut self, deadline: Instant) {
+        self.deadline = Some(deadline);
+    }
+
+    pub fn set_cancel_handle(&mut self, cancel: CancelHandle) {
+        self.cancel = cancel;
+    }
+
+    pub fn capture_output(&mut self) {
+        self.output = Some(String::new());
+    }
+
+    pub fn take_output(&mut self) -> String {
+        self.output.take().unwrap_or_default()
+    }
+
+    pub const fn disable_input(&mut self) {
+        self.interactive = false;
+    }
+
+    pub fn read_line(&self) -> Option<String> {
+        if !self.interactive {
+            return None;
+        }
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+        }
+    }
+
+    pub const fn disable_color(&mut self) {
+        self.color = false;
+    }
+
+    pub fn set_evaluation(&mut self, evaluation: Evaluation) {
+        self.evaluation = evaluation;
+        self.strict = evaluation == Evaluation::Strict;
+    }
+
+    pub fn warn_lazy_effects(&mut self) {
+        self.lazy_effects = Some(vec![]);
+    }
+
+    fn warn_lazy_effect(&mut self, location: &Rc<Location>) {
+        let (Some(thunk), Some(warned)) = (self.forcing.first(), &mut self.lazy_effects) else {
+            return;
+        };
+        if warned.contains(location) {
+            return;
+        }
+        eprintln!(
+            "Warning: effectful call at {location} ran while forcing the lazy value at {thunk}\nHelp: evaluate it directly in a statement, or use #strict to pass arguments eagerly"
+        );
+        warned.push(location.clone());
+    }
+
+    pub fn colors_enabled(&self) -> bool {
+        self.color
+            && self.output.is_none()
+            && env::var_os("NO_COLOR").is_none()
+            && io::stdout().is_terminal()
+    }
+
+    pub fn gensym(&mut self, prefix: &str) -> String {
+        self.symbols += 1;
+        format!("{prefix}_{}", self.symbols)
+    }
+
+    pub fn on_exit(&mut self, hook: Value, location: Rc<Location>) {
+        self.exit_hooks.push((hook, location));
+    }
+
+    pub fn on_interrupt(&mut self, handler: Value) {
+        signal::install();
+        self.interrupt_handler = Some(handler);
+    }
+
+    pub fn run_exit_hooks(&mut self, result: Result<()>) -> Result<()> {
+        let code = match &result {
+            Ok(()) => 0,
+            Err(Error {
+                kind: ErrorKind::Exit(code),
+                ..
+            }) => *code,
+            Err(Error {
+                kind: ErrorKind::Interrupted,
+                ..
+            }) => 130,
+            Err(_) => 1,
+        };
+
+        let mut result = result;
+        while let Some((hook, location)) = self.exit_hooks.pop() {
+            let outcome = self
+                .apply(hook, Value::Integer(code.into()), location)
+                .and_then(|value| self.force(value));
+            if let (Ok(()), Err(e)) = (&result, outcome) {
+                result = Err(e);
+            }
+        }
+        result
+    }
+
+    pub fn collect_tests(&mut self) {
+        self.tests = Some(vec![]);
+    }
+
+    pub fn run_tests(&mut self) -> Vec<TestCase> {
+        let tests = self.tests.take().unwrap_or_default();
+        tests
+            .into_iter()
+            .map(|(name, body)| {
+                let start = Instant::now();
+                let location = body.location.clone();
+                self.environment = self.globals.clone();
+                let result = self
+                    .evaluate(body)
+                    .and_then(|value| self.force(value))
+                    .and_then(|value| match value {
+                        Value::Boolean(false) => err!(
+                            ErrorKind::AssertionFailed,
+                            location,
+                            "Test evaluated to false",
+                        ),
+                        _ => Ok(()),
+                    });
+                TestCase {
+                    name,
+                    result,
+                    duration: start.elapsed(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn record_timings(&mut self) {
+        self.timings = Some(vec![]);
+    }
+
+    pub fn push_timing(&mut self, timing: Timing) {
+        if let Some(timings) = &mut self.timings {
+            timings.push(timing);
+        }
+    }
+
+    pub fn take_timings(&mut self) -> Vec<Timing> {
+        self.timings.take().unwrap_or_default()
+    }
+
+    pub fn write_line(&mut self, line: &str) {
+        match &mut self.output {
+            Some(output) => {
+                output.push_str(line);
+                output.push('\n');
+            }
+            None => println!("{line}"),
         }
     }
 
     pub fn interpret(&mut self, statements: Vec<Located<Statement>>) -> Result<()> {
+        Self::check(&statements)?;
+        self.execute_all(statements)
+    }
+
+    pub fn check(statements: &[Located<Statement>]) -> Result<()> {
+        Self::check_declarations(statements)?;
+        checker::check(statements)
+            .into_iter()
+            .next()
+            .map_or(Ok(()), Err)
+    }
+
+    pub fn execute_all(&mut self, statements: Vec<Located<Statement>>) -> Result<()> {
         for statement in statements {
+            let definitions = match &statement.node {
+                Statement::Mutual { definitions } => definitions.iter().collect(),
+                _ => vec![&statement],
+            };
+            for name in definitions
+                .into_iter()
+                .filter_map(|definition| definition.node.defined_name())
+                .filter_map(|name| name.node.get_identifier_name())
+            {
+                self.defined_in.insert(name, self.current_file.clone());
+            }
             self.execute(statement)?;
         }
         Ok(())
     }
 
+    pub fn interpret_value(&mut self, mut statements: Vec<Located<Statement>>) -> Result<Value> {
+        let last = match statements.pop() {
+            Some(Located {
+                node: Statement::Expression { expression },
+                ..
+            }) => Some(expression),
+            Some(statement) => {
+                statements.push(statement);
+                None
+            }
+            None => None,
+        };
+
+        self.interpret(statements)?;
+        match last {
+            Some(expression) => {
+                let value = self.evaluate(expression)?;
+                self.force(value)
+            }
+            None => Ok(Value::None),
+        }
+    }
+
     fn execute(&mut self, statement: Located<Statement>) -> Result<()> {
+        if let Some(name) = statement.node.defined_name() {
+            self.check_signature(name, statement.node.arity())?;
+        }
+
         match statement.node {
-            Statement::Declaration { .. } => {
-                todo!()
+            Statement::Attributed {
+                attributes,
+                statement,
+            } if attributes.iter().any(|attribute| attribute.is("test")) => {
+                if let Statement::Variable { name, body } = statement.node
+                    && let Some(name) = name.node.get_identifier_name()
+                    && let Some(tests) = &mut self.tests
+                {
+                    tests.push((name, body));
+                }
+                Ok(())
+            }
+            Statement::Attributed { statement, .. } => self.execute(*statement),
+            Statement::Declaration { name, types } => {
+                self.execute_declaration(&name, types, statement.location)
             }
             Statement::Definition {
                 name,
@@ -59,65 +378,59 @@ impl Interpreter {
                 })?;
 
                 let environment = Environment::with_parent(self.environment.clone());
-                self.environment.borrow_mut().set(
-                    name,
-                    Value::Function {
-                        parameter,
-                        body,
-                        environment,
-                    },
-                );
+                let function = Value::function(parameter, body, environment.clone());
+                environment.borrow_mut().set(name.clone(), function.clone());
+                self.environment.borrow_mut().set(name, function);
                 Ok(())
             }
             Statement::Expression { expression } => {
                 let value = self.evaluate(expression)?;
-                println!("Value({})", self.force(value)?);
+                let value = self.force(value)?;
+                self.write_line(&format!("Value({value})"));
                 Ok(())
             }
-            Statement::Use { path } => {
-                let mut relative_path = String::new();
-                for (i, part) in path.iter().enumerate() {
-                    if let TokenValue::Identifier(ref s) = part.node.value {
-                        if i > 0 {
-                            relative_path.push('/');
-                        }
-                        relative_path.push_str(s);
-                    } else {
-                        return Err(Error::with_help(
-                            ErrorKind::InvalidArguments,
-                            part.location.clone(),
-                            "Import path must be identifiers",
-                        ));
-                    }
-                }
-                relative_path.push_str(".icy");
+            Statement::Mutual { definitions } => {
+                let group = Environment::with_parent(self.environment.clone());
+                let outer = std::mem::replace(&mut self.environment, group.clone());
+                let result = self.interpret(definitions);
+                self.environment = outer;
+                result?;
 
-                let base_dir = self
-                    .current_file
-                    .as_ref()
-                    .and_then(|p| p.parent().map(std::path::Path::to_path_buf))
-                    .unwrap_or_else(|| PathBuf::from("."));
-                let file_path = base_dir.join(&relative_path);
-
-                let source = std::fs::read_to_string(&file_path).map_err(|_| {
+                for (name, value) in group.borrow().bindings() {
+                    self.environment
+                        .borrow_mut()
+                        .set(name.clone(), value.clone());
+                }
+                Ok(())
+            }
+            Statement::Data { name, constructors } => {
+                self.execute_data(&name, &constructors);
+                Ok(())
+            }
+            Statement::Newtype { name } => {
+                let name: String = name.node.get_identifier_name().ok_or_else(|| {
                     Error::with_help(
-                        ErrorKind::InvalidArguments,
-                        path[0].location.clone(),
-                        format!("Could not read import file: {}", file_path.display()),
+                        ErrorKind::InvalidToken,
+                        statement.location.clone(),
+                        "Newtype name must be an identifier",
                     )
                 })?;
 
-                let mut lexer = Lexer::new();
-                let tokens = lexer.lex(&source)?;
-                let mut parser = Parser::new();
-                let ast = parser.parse(tokens)?;
-
-                let prev_file = self.current_file.take();
-                self.current_file = Some(file_path);
-                let result = self.interpret(ast);
-                self.current_file = prev_file;
-                result
+                let constructor = Value::builtin(
+                    Purity::Pure,
+                    tag(Tag::Newtype {
+                        name: name.clone(),
+                        module: self.current_file.clone(),
+                    }),
+                );
+                self.environment.borrow_mut().set(name, constructor);
+                Ok(())
             }
+            Statement::Pragma { .. } => {
+                self.strict = true;
+                Ok(())
+            }
+            Statement::Use { path } => self.execute_use(&path),
             Statement::Variable { name, body } => {
                 let name: String = name.node.get_identifier_name().ok_or_else(|| {
                     Error::with_help(
@@ -134,7 +447,116 @@ impl Interpreter {
         }
     }
 
+    fn execute_declaration(
+        &self,
+        name: &Located<Token>,
+        types: Vec<Located<Token>>,
+        location: Rc<Location>,
+    ) -> Result<()> {
+        let name = name.node.get_identifier_name().ok_or_else(|| {
+            Error::with_help(
+                ErrorKind::InvalidToken,
+                location,
+                "Declared name must be an identifier",
+            )
+        })?;
+        self.environment.borrow_mut().declare(name, types);
+        Ok(())
+    }
+
+    fn execute_data(&self, name: &Located<Token>, constructors: &[Located<Token>]) {
+        let data: Rc<str> = name.node.get_identifier_name().unwrap_or_default().into();
+        for (index, constructor) in constructors.iter().enumerate() {
+            let name = constructor.node.get_identifier_name().unwrap_or_default();
+            let value = Value::Constructor {
+                data: data.clone(),
+                name: name.clone(),
+                index,
+            };
+            self.environment.borrow_mut().set(name, value);
+        }
+    }
+
+    pub fn check_declarations(statements: &[Located<Statement>]) -> Result<()> {
+        for (i, statement) in statements.iter().enumerate() {
+            let Statement::Declaration { name, .. } = &statement.node else {
+                continue;
+            };
+            let defined = statements[i + 1..].iter().any(|later| {
+                later
+                    .node
+                    .defined_name()
+                    .is_some_and(|defined| defined.node.value == name.node.value)
+            });
+            if !defined {
+                return err!(
+                    ErrorKind::UnimplementedFunction,
+                    statement.location.clone(),
+                    format!(
+                        "{} is declared but no definition follows",
+                        name.node.get_identifier_name().unwrap_or_default()
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn check_signature(&self, name: &Located<Token>, arity: usize) -> Result<()> {
+        let Some(identifier) = name.node.get_identifier_name() else {
+            return Ok(());
+        };
+        let environment = self.environment.borrow();
+        let Some(types) = environment.signature(&identifier) else {
+            return Ok(());
+        };
+        if types.len() <= arity {
+            return err!(
+                ErrorKind::MismatchedTypes,
+                name.location.clone(),
+                format!(
+                    "{identifier} is declared with {} parameters but defined with {arity}",
+                    types.len().saturating_sub(1)
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    fn execute_use(&mut self, path: &[Located<Token>]) -> Result<()> {
+        let file_path = resolver::import_path(self.current_file.as_deref(), path)?;
+
+        let source = std::fs::read_to_string(&file_path).map_err(|_| {
+            Error::with_help(
+                ErrorKind::InvalidArguments,
+                path[0].location.clone(),
+                format!("Could not read import file: {}", file_path.display()),
+            )
+        })?;
+
+        let (ast, times) = self.cache.parse_timed(&source)?;
+        self.add_module(Some(file_path.clone()), &source);
+
+        let prev_file = self.current_file.take();
+        let tests = self.tests.take();
+        let strict = std::mem::replace(&mut self.strict, self.evaluation == Evaluation::Strict);
+        self.current_file = Some(file_path.clone());
+        let start = Instant::now();
+        let result = self.interpret(ast);
+        self.push_timing(Timing {
+            file: Some(file_path),
+            times,
+            interpret: start.elapsed(),
+        });
+        self.current_file = prev_file;
+        self.tests = tests;
+        self.strict = strict;
+        result
+    }
+
     fn evaluate(&mut self, expression: Located<Expression>) -> Result<Value> {
+        self.checkpoint(&expression.location)?;
+
         match expression.node {
             Expression::Unary {
                 operator,
@@ -144,7 +566,8 @@ impl Interpreter {
                 left,
                 operator,
                 right,
-            } => self.evaluate_binary(*left, operator, *right),
+            } => self.evaluate_binary(*left, &operator, *right),
+            Expression::Block { statements } => self.evaluate_block(statements),
             Expression::Call { function, argument } => self.evaluate_call(*function, *argument),
             Expression::Identifier { token } => self.evaluate_identifier(&token),
             Expression::If {
@@ -152,10 +575,57 @@ impl Interpreter {
                 otherwise,
             } => self.evaluate_if(branches, *otherwise),
             Expression::Lambda { parameter, body } => self.evaluate_lambda(parameter, *body),
+            Expression::Let { name, value, body } => self.evaluate_let(&name, *value, *body),
+            Expression::List { elements } => elements
+                .into_iter()
+                .map(|element| {
+                    let value = self.evaluate(element)?;
+                    self.force(value)
+                })
+                .collect::<Result<_>>()
+                .map(Value::List),
+            Expression::Record { fields } => fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = self.evaluate(value)?;
+                    Ok((
+                        name.node.get_identifier_name().unwrap_or_default(),
+                        self.force(value)?,
+                    ))
+                })
+                .collect::<Result<_>>()
+                .map(Value::Record),
+            Expression::Field { record, field } => self.evaluate_field(*record, &field),
+            Expression::Match { arguments, clauses } => {
+                self.evaluate_match(&arguments, clauses, expression.location)
+            }
             Expression::Literal { token } => (&token).try_into(),
         }
     }
 
+    fn evaluate_field(
+        &mut self,
+        record: Located<Expression>,
+        field: &Located<Token>,
+    ) -> Result<Value> {
+        let name = field.node.get_identifier_name().unwrap_or_default();
+        let record = self.evaluate(record)?;
+        match self.force(record)? {
+            Value::Record(mut fields) => fields.remove(&name).ok_or_else(|| {
+                Error::with_help(
+                    ErrorKind::InvalidIdentifier,
+                    field.location.clone(),
+                    format!("Record has no field {name}"),
+                )
+            }),
+            other => err!(
+                ErrorKind::MismatchedTypes,
+                field.location.clone(),
+                format!("Cannot access field {name} of {other:?}, it is not a record"),
+            ),
+        }
+    }
+
     fn evaluate_unary(
         &mut self,
         operator: Located<Token>,
@@ -177,6 +647,7 @@ impl Interpreter {
                 let value = self.evaluate(expression)?;
                 match self.force(value)? {
                     Value::Integer(i) => Ok(Value::Integer(-i)),
+                    Value::Float(f) => Ok(Value::Float(-f)),
                     _ => err!(
                         ErrorKind::InvalidArguments,
                         operator.location,
@@ -195,7 +666,7 @@ impl Interpreter {
     fn evaluate_binary(
         &mut self,
         left: Located<Expression>,
-        operator: Located<Token>,
+        operator: &Located<Token>,
         right: Located<Expression>,
     ) -> Result<Value> {
         let left_value = self.evaluate(left)?;
@@ -203,115 +674,337 @@ impl Interpreter {
         let left_forced = self.force(left_value)?;
         let right_forced = self.force(right_value)?;
 
-        match (operator.node.kind, left_forced, right_forced) {
-            (TokenKind::Plus, Value::Integer(l), Value::Integer(r)) => l
-                .checked_add(r)
-                .map(Value::Integer)
-                .ok_or_else(|| Error::new(ErrorKind::Overflow, operator.location.clone())),
-            (TokenKind::Plus, Value::String(l), Value::String(r)) => Ok(Value::String(l + &r)),
-
-            (TokenKind::Minus, Value::Integer(l), Value::Integer(r)) => l
-                .checked_sub(r)
-                .map(Value::Integer)
-                .ok_or_else(|| Error::new(ErrorKind::Overflow, operator.location.clone())),
+        Self::evaluate_binary_values(operator, left_forced, right_forced)
+    }
 
-            (TokenKind::Star, Value::Integer(l), Value::Integer(r)) => l
-                .checked_mul(r)
-                .map(Value::Integer)
-                .ok_or_else(|| Error::new(ErrorKind::Overflow, operator.location.clone())),
+    fn evaluate_cons(operator: &Located<Token>, element: Value, list: Value) -> Result<Value> {
+        let Value::List(mut elements) = list else {
+            return err!(
+                ErrorKind::MismatchedTypes,
+                operator.location.clone(),
+                format!("The right side of : must be a list, found {list:?}"),
+            );
+        };
+        elements.insert(0, element);
+        Ok(Value::List(elements))
+    }
 
-            (TokenKind::StarStar, Value::Integer(l), Value::Integer(r)) => {
-                let exp = match u32::try_from(r) {
-                    Ok(exp) => exp,
-                    Err(_) if (0..=1).contains(&l) => return Ok(Value::Integer(l)),
-                    Err(_) => {
-                        return if r > 0 {
-                            err!(ErrorKind::Overflow, operator.location, "Exponent too large")
-                        } else {
-                            err!(
-                                ErrorKind::InvalidArguments,
-                                operator.location,
-                                "Exponent must be non-negative"
-                            )
-                        };
-                    }
-                };
-                l.checked_pow(exp)
-                    .map(Value::Integer)
-                    .ok_or_else(|| Error::new(ErrorKind::Overflow, operator.location.clone()))
+    fn evaluate_binary_values(
+        operator: &Located<Token>,
+        left: Value,
+        right: Value,
+    ) -> Result<Value> {
+        match (left, right) {
+            (element, list) if operator.node.kind == TokenKind::Colon => {
+                Self::evaluate_cons(operator, element, list)
             }
-
-            (TokenKind::Slash, Value::Integer(l), Value::Integer(r)) => {
-                if r == 0 {
-                    err!(ErrorKind::DivisionByZero, operator.location)
-                } else {
-                    Ok(Value::Integer(l / r))
-                }
+            (Value::Integer(l), Value::Integer(r)) => Self::evaluate_integer_binary(operator, l, r),
+            (Value::Float(l), Value::Float(r)) => Self::evaluate_float_binary(operator, l, r),
+            (Value::Integer(l), Value::Float(r)) => {
+                Self::evaluate_float_binary(operator, numeric::promote(l), r)
             }
-
-            (TokenKind::Percent, Value::Integer(l), Value::Integer(r)) => {
-                if r == 0 {
-                    err!(ErrorKind::DivisionByZero, operator.location)
-                } else {
-                    Ok(Value::Integer(l % r))
+            (Value::Float(l), Value::Integer(r)) => {
+                Self::evaluate_float_binary(operator, l, numeric::promote(r))
+            }
+            (Value::Boolean(l), Value::Boolean(r)) => Self::evaluate_boolean_binary(operator, l, r),
+            (Value::List(mut l), Value::List(r)) if operator.node.kind == TokenKind::Plus => {
+                l.extend(r);
+                Ok(Value::List(l))
+            }
+            (l @ Value::List(_), r @ Value::List(_))
+            | (l @ Value::Record(_), r @ Value::Record(_))
+                if matches!(
+                    operator.node.kind,
+                    TokenKind::EqualEqual | TokenKind::BangEqual
+                ) =>
+            {
+                match (ValueKey::from_value(&l), ValueKey::from_value(&r)) {
+                    (Some(l), Some(r)) => Ok(Value::Boolean(
+                        (l == r) == (operator.node.kind == TokenKind::EqualEqual),
+                    )),
+                    _ => err!(
+                        ErrorKind::MismatchedTypes,
+                        operator.location.clone(),
+                        "Lists and records containing functions cannot be compared",
+                    ),
                 }
             }
-
-            (TokenKind::Ampersand, Value::Integer(l), Value::Integer(r)) => {
-                Ok(Value::Integer(l & r))
+            (l @ (Value::List(_) | Value::String(_)), r) if operator.node.kind.is_ordering() => {
+                Self::evaluate_ordering(operator, &l, &r)
             }
-            (TokenKind::Ampersand, Value::Boolean(l), Value::Boolean(r)) => {
-                Ok(Value::Boolean(l & r))
+            (Value::String(l), Value::String(r)) if operator.node.kind == TokenKind::Plus => {
+                Ok(Value::String(l + &r))
             }
-
-            (TokenKind::Pipe, Value::Integer(l), Value::Integer(r)) => Ok(Value::Integer(l | r)),
-            (TokenKind::Pipe, Value::Boolean(l), Value::Boolean(r)) => Ok(Value::Boolean(l | r)),
-
-            (TokenKind::Caret, Value::Integer(l), Value::Integer(r)) => Ok(Value::Integer(l ^ r)),
-            (TokenKind::Caret, Value::Boolean(l), Value::Boolean(r)) => Ok(Value::Boolean(l ^ r)),
-
-            (TokenKind::BangEqual, Value::Integer(l), Value::Integer(r)) => {
-                Ok(Value::Boolean(l != r))
+            (
+                Value::Tagged {
+                    tag: left_tag,
+                    value: left,
+                },
+                Value::Tagged {
+                    tag: right_tag,
+                    value: right,
+                },
+            ) if left_tag == right_tag => {
+                Self::evaluate_tagged_binary(operator, left_tag, *left, *right)
             }
-            (TokenKind::BangEqual, Value::Boolean(l), Value::Boolean(r)) => {
-                Ok(Value::Boolean(l != r))
+            (
+                Value::Constructor {
+                    data: left_data,
+                    index: left,
+                    ..
+                },
+                Value::Constructor {
+                    data: right_data,
+                    index: right,
+                    ..
+                },
+            ) if left_data == right_data && operator.node.kind.is_comparison() => {
+                Self::evaluate_integer_binary(operator, left as i128, right as i128)
             }
-
-            (TokenKind::EqualEqual, Value::Integer(l), Value::Integer(r)) => {
-                Ok(Value::Boolean(l == r))
+            (left @ Value::Constructor { .. }, right)
+            | (left, right @ Value::Constructor { .. }) => {
+                err!(
+                    ErrorKind::MismatchedTypes,
+                    operator.location.clone(),
+                    format!(
+                        "Cannot combine {left} and {right} with {:?}, constructors can only be compared with constructors of the same data type",
+                        operator.node.kind
+                    ),
+                )
             }
-            (TokenKind::EqualEqual, Value::Boolean(l), Value::Boolean(r)) => {
-                Ok(Value::Boolean(l == r))
+            (left @ Value::Tagged { .. }, right) | (left, right @ Value::Tagged { .. }) => err!(
+                ErrorKind::MismatchedTypes,
+                operator.location.clone(),
+                format!(
+                    "Cannot combine {left} and {right} with {:?}, unwrap them first",
+                    operator.node.kind
+                ),
+            ),
+            (left, right)
+                if matches!(
+                    operator.node.kind,
+                    TokenKind::EqualEqual | TokenKind::BangEqual
+                ) && (left.is_function() || right.is_function()) =>
+            {
+                err!(
+                    ErrorKind::MismatchedTypes,
+                    operator.location.clone(),
+                    "Functions cannot be compared with == or !=, use `same f g` to compare them by identity",
+                )
             }
+            (left, right) => Self::invalid_operands(operator, &left, &right),
+        }
+    }
 
-            (TokenKind::Greater, Value::Integer(l), Value::Integer(r)) => Ok(Value::Boolean(l > r)),
-            (TokenKind::Greater, Value::Boolean(l), Value::Boolean(r)) => {
-                Ok(Value::Boolean(l && !r))
-            }
+    fn evaluate_tagged_binary(
+        operator: &Located<Token>,
+        tag: Tag,
+        left: Value,
+        right: Value,
+    ) -> Result<Value> {
+        match operator.node.kind {
+            TokenKind::Plus | TokenKind::Minus => Ok(Value::Tagged {
+                tag,
+                value: Box::new(Self::evaluate_binary_values(operator, left, right)?),
+            }),
+            kind if kind.is_comparison() => Self::evaluate_binary_values(operator, left, right),
+            _ => err!(
+                ErrorKind::MismatchedTypes,
+                operator.location.clone(),
+                format!(
+                    "{tag} values only support +, - and comparisons, unwrap them to use {:?}",
+                    operator.node.kind
+                ),
+            ),
+        }
+    }
+
+    fn evaluate_integer_binary(operator: &Located<Token>, l: i128, r: i128) -> Result<Value> {
+        let overflow = || Error::new(ErrorKind::Overflow, operator.location.clone());
 
-            (TokenKind::GreaterEqual, Value::Integer(l), Value::Integer(r)) => {
-                Ok(Value::Boolean(l >= r))
+        match operator.node.kind {
+            TokenKind::Plus => l.checked_add(r).map(Value::Integer).ok_or_else(overflow),
+            TokenKind::Minus => l.checked_sub(r).map(Value::Integer).ok_or_else(overflow),
+            TokenKind::Star => l.checked_mul(r).map(Value::Integer).ok_or_else(overflow),
+            TokenKind::StarStar => {
+                let exp = match u32::try_from(r) {
+                    Ok(exp) => exp,
+                    Err(_) if r < 0 && l == 0 => {
+                        return err!(
+                            ErrorKind::DivisionByZero,
+                            operator.location.clone(),
+                            "0 cannot be raised to a negative exponent"
+                        );
+                    }
+                    Err(_) if r < 0 => {
+                        return Ok(Value::Float(numeric::promote(l).powf(numeric::promote(r))));
+                    }
+                    Err(_) if (0..=1).contains(&l) => return Ok(Value::Integer(l)),
+                    Err(_) => {
+                        return err!(
+                            ErrorKind::Overflow,
+                            operator.location.clone(),
+                            "Exponent too large"
+                        );
+                    }
+                };
+                l.checked_pow(exp).map(Value::Integer).ok_or_else(overflow)
             }
-            (TokenKind::GreaterEqual, Value::Boolean(l), Value::Boolean(r)) => {
-                Ok(Value::Boolean(l >= r))
+            TokenKind::Slash | TokenKind::Percent if r == 0 => {
+                err!(ErrorKind::DivisionByZero, operator.location.clone())
             }
+            TokenKind::Slash => Ok(Value::Integer(l / r)),
+            TokenKind::Percent => Ok(Value::Integer(l % r)),
+            TokenKind::Ampersand => Ok(Value::Integer(l & r)),
+            TokenKind::Pipe => Ok(Value::Integer(l | r)),
+            TokenKind::Caret => Ok(Value::Integer(l ^ r)),
+            TokenKind::BangEqual => Ok(Value::Boolean(l != r)),
+            TokenKind::EqualEqual => Ok(Value::Boolean(l == r)),
+            TokenKind::Greater => Ok(Value::Boolean(l > r)),
+            TokenKind::GreaterEqual => Ok(Value::Boolean(l >= r)),
+            TokenKind::Less => Ok(Value::Boolean(l < r)),
+            TokenKind::LessEqual => Ok(Value::Boolean(l <= r)),
+            _ => Self::invalid_operands(operator, &Value::Integer(l), &Value::Integer(r)),
+        }
+    }
 
-            (TokenKind::Less, Value::Integer(l), Value::Integer(r)) => Ok(Value::Boolean(l < r)),
-            (TokenKind::Less, Value::Boolean(l), Value::Boolean(r)) => Ok(Value::Boolean(!l & r)),
+    fn evaluate_boolean_binary(operator: &Located<Token>, l: bool, r: bool) -> Result<Value> {
+        match operator.node.kind {
+            TokenKind::Ampersand => Ok(Value::Boolean(l & r)),
+            TokenKind::Pipe => Ok(Value::Boolean(l | r)),
+            TokenKind::Caret => Ok(Value::Boolean(l ^ r)),
+            TokenKind::BangEqual => Ok(Value::Boolean(l != r)),
+            TokenKind::EqualEqual => Ok(Value::Boolean(l == r)),
+            TokenKind::Greater => Ok(Value::Boolean(l && !r)),
+            TokenKind::GreaterEqual => Ok(Value::Boolean(l >= r)),
+            TokenKind::Less => Ok(Value::Boolean(!l & r)),
+            TokenKind::LessEqual => Ok(Value::Boolean(l <= r)),
+            _ => Self::invalid_operands(operator, &Value::Boolean(l), &Value::Boolean(r)),
+        }
+    }
 
-            (TokenKind::LessEqual, Value::Integer(l), Value::Integer(r)) => {
-                Ok(Value::Boolean(l <= r))
-            }
-            (TokenKind::LessEqual, Value::Boolean(l), Value::Boolean(r)) => {
-                Ok(Value::Boolean(l <= r))
+    #[expect(
+        clippy::float_cmp,
+        reason = "== and != on floats are exact comparisons in the language"
+    )]
+    fn evaluate_float_binary(operator: &Located<Token>, l: f64, r: f64) -> Result<Value> {
+        match operator.node.kind {
+            TokenKind::Plus => Ok(Value::Float(l + r)),
+            TokenKind::Minus => Ok(Value::Float(l - r)),
+            TokenKind::Star => Ok(Value::Float(l * r)),
+            TokenKind::StarStar => Ok(Value::Float(l.powf(r))),
+            TokenKind::Slash | TokenKind::Percent if r == 0.0 => {
+                err!(ErrorKind::DivisionByZero, operator.location.clone())
             }
+            TokenKind::Slash => Ok(Value::Float(l / r)),
+            TokenKind::Percent => Ok(Value::Float(l % r)),
+            TokenKind::BangEqual => Ok(Value::Boolean(l != r)),
+            TokenKind::EqualEqual => Ok(Value::Boolean(l == r)),
+            TokenKind::Greater => Ok(Value::Boolean(l > r)),
+            TokenKind::GreaterEqual => Ok(Value::Boolean(l >= r)),
+            TokenKind::Less => Ok(Value::Boolean(l < r)),
+            TokenKind::LessEqual => Ok(Value::Boolean(l <= r)),
+            _ => Self::invalid_operands(operator, &Value::Float(l), &Value::Float(r)),
+        }
+    }
 
-            (op, left, right) => err!(
+    fn evaluate_ordering(operator: &Located<Token>, left: &Value, right: &Value) -> Result<Value> {
+        let ordering = Self::compare_values(operator, left, right)?;
+        Ok(Value::Boolean(match operator.node.kind {
+            TokenKind::Less => ordering.is_lt(),
+            TokenKind::LessEqual => ordering.is_le(),
+            TokenKind::Greater => ordering.is_gt(),
+            _ => ordering.is_ge(),
+        }))
+    }
+
+    fn compare_values(operator: &Located<Token>, left: &Value, right: &Value) -> Result<Ordering> {
+        let ordering = match (left, right) {
+            (Value::Integer(l), Value::Integer(r)) => Some(l.cmp(r)),
+            (Value::Float(l), Value::Float(r)) => l.partial_cmp(r),
+            (Value::Integer(l), Value::Float(r)) => numeric::promote(*l).partial_cmp(r),
+            (Value::Float(l), Value::Integer(r)) => l.partial_cmp(&numeric::promote(*r)),
+            (Value::Boolean(l), Value::Boolean(r)) => Some(l.cmp(r)),
+            (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+            (Value::List(l), Value::List(r)) => {
+                for (l, r) in l.iter().zip(r) {
+                    let ordering = Self::compare_values(operator, l, r)?;
+                    if ordering.is_ne() {
+                        return Ok(ordering);
+                    }
+                }
+                Some(l.len().cmp(&r.len()))
+            }
+            (
+                Value::Constructor {
+                    data: left_data,
+                    index: l,
+                    ..
+                },
+                Value::Constructor {
+                    data: right_data,
+                    index: r,
+                    ..
+                },
+            ) if left_data == right_data => Some(l.cmp(r)),
+            (
+                Value::Tagged {
+                    tag: left_tag,
+                    value: l,
+                },
+                Value::Tagged {
+                    tag: right_tag,
+                    value: r,
+                },
+            ) if left_tag == right_tag => return Self::compare_values(operator, l, r),
+            _ => {
+                return err!(
+                    ErrorKind::MismatchedTypes,
+                    operator.location.clone(),
+                    format!(
+                        "Cannot order {left} and {right} with {:?}",
+                        operator.node.kind
+                    ),
+                );
+            }
+        };
+        ordering.ok_or_else(|| {
+            Error::with_help(
                 ErrorKind::InvalidArguments,
-                operator.location,
-                format!("{:?} and {:?} have invalid types for {:?}", left, right, op),
+                operator.location.clone(),
+                "NaN cannot be ordered",
+            )
+        })
+    }
+
+    fn invalid_operands(operator: &Located<Token>, left: &Value, right: &Value) -> Result<Value> {
+        err!(
+            ErrorKind::InvalidArguments,
+            operator.location.clone(),
+            format!(
+                "{:?} and {:?} have invalid types for {:?}",
+                left, right, operator.node.kind
             ),
+        )
+    }
+
+    fn evaluate_block(&mut self, statements: Vec<Located<Statement>>) -> Result<Value> {
+        let scope = Environment::with_parent(self.environment.clone());
+        let outer = std::mem::replace(&mut self.environment, scope);
+        let mut result = Ok(Value::None);
+        for statement in statements {
+            result = match statement.node {
+                Statement::Expression { expression } => self
+                    .evaluate(expression)
+                    .and_then(|value| self.force(value)),
+                _ => self.execute(statement).map(|()| Value::None),
+            };
+            if result.is_err() {
+                break;
+            }
         }
+        self.environment = outer;
+        result
     }
 
     fn evaluate_call(
@@ -321,12 +1014,35 @@ impl Interpreter {
     ) -> Result<Value> {
         let location = function.location.clone();
         let function_value = self.evaluate(function)?;
+        let function_value = self.force(function_value)?;
+
+        let argument = if self.strict {
+            let argument = self.evaluate(argument)?;
+            self.force(argument)?
+        } else if matches!(function_value, Value::BuiltinFunction { .. }) {
+            self.evaluate(argument)?
+        } else {
+            Value::Thunk {
+                expression: argument,
+                environment: self.environment.clone(),
+            }
+        };
+
+        self.apply(function_value, argument, location)
+    }
 
-        match self.force(function_value)? {
+    pub fn apply(
+        &mut self,
+        function: Value,
+        argument: Value,
+        location: Rc<Location>,
+    ) -> Result<Value> {
+        match function {
             Value::Function {
                 parameter,
                 body,
                 environment,
+                ..
             } => {
                 let old_environment = self.environment.clone();
                 let function_environment = Environment::with_parent(environment);
@@ -335,21 +1051,20 @@ impl Interpreter {
                     .node
                     .get_identifier_name()
                     .ok_or_else(|| Error::new(ErrorKind::InvalidToken, parameter.location))?;
-                let thunk = Value::Thunk {
-                    expression: argument,
-                    environment: self.environment.clone(),
-                };
-                function_environment.borrow_mut().set(parameter_name, thunk);
+                function_environment
+                    .borrow_mut()
+                    .set(parameter_name, argument);
 
                 self.environment = function_environment;
-                let res = self.evaluate(body)?;
+                let result = self.evaluate(body);
                 self.environment = old_environment;
-
-                Ok(res)
+                result
             }
-            Value::BuiltinFunction { function } => {
-                let value = self.evaluate(argument)?;
-                function(value, location)
+            Value::BuiltinFunction { function, purity } => {
+                if purity == Purity::Effectful {
+                    self.warn_lazy_effect(&location);
+                }
+                function(self, argument, location)
             }
             _ => err!(
                 ErrorKind::ExpectedExpression,
@@ -385,17 +1100,92 @@ impl Interpreter {
         self.evaluate(otherwise)
     }
 
+    fn evaluate_let(
+        &mut self,
+        name: &Located<Token>,
+        value: Located<Expression>,
+        body: Located<Expression>,
+    ) -> Result<Value> {
+        let name = name
+            .node
+            .get_identifier_name()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidToken, name.location.clone()))?;
+        let value = Value::Thunk {
+            expression: value,
+            environment: self.environment.clone(),
+        };
+
+        let scope = Environment::with_parent(self.environment.clone());
+        scope.borrow_mut().set(name, value);
+        let outer = std::mem::replace(&mut self.environment, scope);
+        let result = self.evaluate(body);
+        self.environment = outer;
+        result
+    }
+
+    fn evaluate_match(
+        &mut self,
+        arguments: &[Located<Token>],
+        clauses: Vec<(Vec<Located<Token>>, Located<Expression>)>,
+        location: Rc<Location>,
+    ) -> Result<Value> {
+        let mut values = arguments
+            .iter()
+            .map(|argument| {
+                let name = argument.node.get_identifier_name().unwrap_or_default();
+                self.environment.borrow().get(&name).ok_or_else(|| {
+                    Error::new(ErrorKind::InvalidIdentifier, argument.location.clone())
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        'clauses: for (patterns, body) in clauses {
+            let scope = Environment::with_parent(self.environment.clone());
+            for (pattern, value) in patterns.iter().zip(&mut values) {
+                match pattern.node.kind {
+                    TokenKind::Underscore => {}
+                    TokenKind::Identifier => {
+                        let name = pattern.node.get_identifier_name().unwrap_or_default();
+                        scope.borrow_mut().set(name, value.clone());
+                    }
+                    _ => {
+                        *value = self.force(value.clone())?;
+                        let literal = Value::try_from(pattern)?;
+                        if ValueKey::from_value(&literal) != ValueKey::from_value(value) {
+                            continue 'clauses;
+                        }
+                    }
+                }
+            }
+
+            let outer = std::mem::replace(&mut self.environment, scope);
+            let result = self.evaluate(body);
+            self.environment = outer;
+            return result;
+        }
+
+        let values = values
+            .into_iter()
+            .map(|value| self.force(value).map(|value| value.to_string()))
+            .collect::<Result<Vec<_>>>()?;
+        err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("No equation matches the arguments {}", values.join(" ")),
+        )
+    }
+
     fn evaluate_lambda(
         &self,
         parameter: Located<Token>,
         body: Located<Expression>,
     ) -> Result<Value> {
         if TokenKind::Identifier == parameter.node.kind {
-            Ok(Value::Function {
+            Ok(Value::function(
                 parameter,
                 body,
-                environment: Environment::with_parent(self.environment.clone()),
-            })
+                Environment::with_parent(self.environment.clone()),
+            ))
         } else {
             err!(
                 ErrorKind::InvalidToken,
@@ -405,7 +1195,45 @@ impl Interpreter {
         }
     }
 
-    fn force(&mut self, value: Value) -> Result<Value> {
+    fn checkpoint(&mut self, location: &Rc<Location>) -> Result<()> {
+        self.steps = self.steps.wrapping_add(1);
+        if !self.steps.is_multiple_of(1024) {
+            return Ok(());
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return err!(
+                ErrorKind::Timeout,
+                location.clone(),
+                "Evaluation did not finish before its deadline",
+            );
+        }
+        if self.cancel.is_cancelled() {
+            return err!(
+                ErrorKind::Cancelled,
+                location.clone(),
+                "Evaluation was cancelled by the host",
+            );
+        }
+        if signal::take_interrupt() {
+            if let Some(handler) = self.interrupt_handler.take() {
+                let handled = self
+                    .apply(handler, Value::None, location.clone())
+                    .and_then(|value| self.force(value));
+                handled?;
+            }
+            return err!(
+                ErrorKind::Interrupted,
+                location.clone(),
+                "Evaluation was interrupted",
+            );
+        }
+        Ok(())
+    }
+
+    pub fn force(&mut self, value: Value) -> Result<Value> {
         match value {
             Value::Thunk {
                 expression,
@@ -413,9 +1241,16 @@ impl Interpreter {
             } => {
                 let old_environment = self.environment.clone();
                 self.environment = environment;
-                let value = self.evaluate(expression)?;
+                let warn = self.lazy_effects.is_some();
+                if warn {
+                    self.forcing.push(expression.location.clone());
+                }
+                let value = self.evaluate(expression);
+                if warn {
+                    self.forcing.pop();
+                }
                 self.environment = old_environment;
-                self.force(value)
+                self.force(value?)
             }
             other => Ok(other),
         }
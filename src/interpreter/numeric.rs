@@ -0,0 +1,287 @@
+use super::Interpreter;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Value};
+use std::rc::Rc;
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "integers are promoted to the nearest float in mixed arithmetic"
+)]
+pub const fn promote(integer: i128) -> f64 {
+    integer as f64
+}
+
+pub fn to_float(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    match interpreter.force(value)? {
+        Value::Integer(i) => Ok(Value::Float(promote(i))),
+        Value::Float(f) => Ok(Value::Float(f)),
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("toFloat expects a number, found {other:?}"),
+        ),
+    }
+}
+
+pub fn truncate(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    to_integer(interpreter, value, location, "truncate", f64::trunc)
+}
+
+pub fn round(interpreter: &mut Interpreter, value: Value, location: Rc<Location>) -> Result<Value> {
+    to_integer(interpreter, value, location, "round", f64::round)
+}
+
+pub fn floor_int(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    to_integer(interpreter, value, location, "floorInt", f64::floor)
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "the float is already rounded and checked to be within range"
+)]
+fn to_integer(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+    name: &str,
+    rounding: fn(f64) -> f64,
+) -> Result<Value> {
+    match interpreter.force(value)? {
+        Value::Integer(i) => Ok(Value::Integer(i)),
+        Value::Float(f) => {
+            let rounded = rounding(f);
+            if rounded.is_finite() && rounded.abs() < promote(i128::MAX) {
+                Ok(Value::Integer(rounded as i128))
+            } else {
+                err!(
+                    ErrorKind::Overflow,
+                    location,
+                    format!("{name} cannot represent {f} as an integer"),
+                )
+            }
+        }
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects a number, found {other:?}"),
+        ),
+    }
+}
+
+pub fn sum(interpreter: &mut Interpreter, list: Value, location: Rc<Location>) -> Result<Value> {
+    fold(
+        interpreter,
+        list,
+        location,
+        "sum",
+        0,
+        i128::checked_add,
+        |l, r| l + r,
+    )
+}
+
+pub fn product(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    fold(
+        interpreter,
+        list,
+        location,
+        "product",
+        1,
+        i128::checked_mul,
+        |l, r| l * r,
+    )
+}
+
+pub fn minimum(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    extreme(interpreter, list, location, "minimum", |number, best| {
+        number < best
+    })
+}
+
+pub fn maximum(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    extreme(interpreter, list, location, "maximum", |number, best| {
+        number > best
+    })
+}
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "the average of a list is always a float"
+)]
+pub fn average(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let numbers = numbers(interpreter, list, &location, "average")?;
+    if numbers.is_empty() {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "average of an empty list is undefined",
+        );
+    }
+    let total: f64 = numbers.iter().map(as_float).sum();
+    Ok(Value::Float(total / numbers.len() as f64))
+}
+
+pub fn clamp(interpreter: &mut Interpreter, low: Value, location: Rc<Location>) -> Result<Value> {
+    let low = number(interpreter, low, location, "clamp")?;
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, high, location| {
+            let high = number(interpreter, high, location.clone(), "clamp")?;
+            if as_float(&low) > as_float(&high) {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    format!(
+                        "clamp expects the lower bound {low} to be at most the upper bound {high}"
+                    ),
+                );
+            }
+            let low = low.clone();
+            Ok(Value::builtin(
+                Purity::Pure,
+                move |interpreter, value, location| {
+                    let value = number(interpreter, value, location, "clamp")?;
+                    Ok(if as_float(&value) < as_float(&low) {
+                        low.clone()
+                    } else if as_float(&value) > as_float(&high) {
+                        high.clone()
+                    } else {
+                        value
+                    })
+                },
+            ))
+        },
+    ))
+}
+
+fn fold(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+    name: &str,
+    initial: i128,
+    integer: fn(i128, i128) -> Option<i128>,
+    float: fn(f64, f64) -> f64,
+) -> Result<Value> {
+    numbers(interpreter, list, &location, name)?
+        .into_iter()
+        .try_fold(Value::Integer(initial), |total, number| {
+            combine(&total, &number, integer, float)
+        })
+        .ok_or_else(|| {
+            Error::with_help(
+                ErrorKind::Overflow,
+                location,
+                format!("{name} does not fit in an integer"),
+            )
+        })
+}
+
+fn extreme(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: Rc<Location>,
+    name: &str,
+    better: fn(f64, f64) -> bool,
+) -> Result<Value> {
+    numbers(interpreter, list, &location, name)?
+        .into_iter()
+        .reduce(|best, number| {
+            if better(as_float(&number), as_float(&best)) {
+                number
+            } else {
+                best
+            }
+        })
+        .ok_or_else(|| {
+            Error::with_help(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("{name} of an empty list is undefined"),
+            )
+        })
+}
+
+fn numbers(
+    interpreter: &mut Interpreter,
+    list: Value,
+    location: &Rc<Location>,
+    name: &str,
+) -> Result<Vec<Value>> {
+    let Value::List(elements) = interpreter.force(list)? else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location.clone(),
+            format!("{name} expects a list of numbers"),
+        );
+    };
+    elements
+        .into_iter()
+        .map(|element| number(interpreter, element, location.clone(), name))
+        .collect()
+}
+
+fn number(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: Rc<Location>,
+    name: &str,
+) -> Result<Value> {
+    match interpreter.force(value)? {
+        number @ (Value::Integer(_) | Value::Float(_)) => Ok(number),
+        other => err!(
+            ErrorKind::InvalidArguments,
+            location,
+            format!("{name} expects numbers, found {other:?}"),
+        ),
+    }
+}
+
+fn combine(
+    left: &Value,
+    right: &Value,
+    integer: fn(i128, i128) -> Option<i128>,
+    float: fn(f64, f64) -> f64,
+) -> Option<Value> {
+    match (left, right) {
+        (Value::Integer(l), Value::Integer(r)) => integer(*l, *r).map(Value::Integer),
+        (l, r) => Some(Value::Float(float(as_float(l), as_float(r)))),
+    }
+}
+
+const fn as_float(number: &Value) -> f64 {
+    match number {
+        Value::Integer(i) => promote(*i),
+        Value::Float(f) => *f,
+        _ => f64::NAN,
+    }
+}
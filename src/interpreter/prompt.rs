@@ -0,0 +1,113 @@
+use super::Interpreter;
+use super::builtins::string_argument;
+use crate::err;
+use crate::error::{ErrorKind, Result};
+use crate::model::{Location, Purity, Value};
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+pub fn prompt(
+    interpreter: &mut Interpreter,
+    message: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let message = string_argument(interpreter, message, location, "prompt")?;
+    show(&format!("{message} "));
+    Ok(interpreter.read_line().map_or(Value::None, Value::String))
+}
+
+pub fn confirm(
+    interpreter: &mut Interpreter,
+    message: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let message = string_argument(interpreter, message, location, "confirm")?;
+    loop {
+        show(&format!("{message} [y/N] "));
+        let Some(answer) = interpreter.read_line() else {
+            return Ok(Value::Boolean(false));
+        };
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(Value::Boolean(true)),
+            "" | "n" | "no" => return Ok(Value::Boolean(false)),
+            _ => show("Please answer y or n.\n"),
+        }
+    }
+}
+
+pub fn select(
+    interpreter: &mut Interpreter,
+    options: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let Value::List(options) = interpreter.force(options)? else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "select expects a list of options",
+        );
+    };
+    if options.is_empty() {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "select expects at least one option",
+        );
+    }
+
+    Ok(Value::builtin(
+        Purity::Effectful,
+        move |interpreter, message, location| {
+            let message = string_argument(interpreter, message, location, "select")?;
+            show(&format!("{message}\n"));
+            for (i, option) in options.iter().enumerate() {
+                show(&format!("  {}) {option}\n", i + 1));
+            }
+            loop {
+                show(&format!("Choose 1-{}: ", options.len()));
+                let Some(answer) = interpreter.read_line() else {
+                    return Ok(Value::None);
+                };
+                match answer.trim().parse::<usize>() {
+                    Ok(choice) if (1..=options.len()).contains(&choice) => {
+                        return Ok(options[choice - 1].clone());
+                    }
+                    _ => show("Please enter one of the listed numbers.\n"),
+                }
+            }
+        },
+    ))
+}
+
+pub fn password(
+    interpreter: &mut Interpreter,
+    message: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let message = string_argument(interpreter, message, location, "password")?;
+    show(&format!("{message} "));
+    let hidden = io::stdin().is_terminal() && set_echo(false);
+    let line = interpreter.read_line();
+    if hidden {
+        set_echo(true);
+        show("\n");
+    }
+    Ok(line.map_or(Value::None, Value::String))
+}
+
+fn show(text: &str) {
+    if io::stdin().is_terminal() {
+        let mut stderr = io::stderr();
+        let _ = stderr.write_all(text.as_bytes());
+        let _ = stderr.flush();
+    }
+}
+
+fn set_echo(enabled: bool) -> bool {
+    Command::new("stty")
+        .arg(if enabled { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
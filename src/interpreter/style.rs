@@ -0,0 +1,37 @@
+use super::builtins::string_argument;
+use crate::model::{Purity, Value};
+use std::collections::BTreeMap;
+
+const STYLES: [(&str, u8); 12] = [
+    ("bold", 1),
+    ("dim", 2),
+    ("italic", 3),
+    ("underline", 4),
+    ("black", 30),
+    ("red", 31),
+    ("green", 32),
+    ("yellow", 33),
+    ("blue", 34),
+    ("magenta", 35),
+    ("cyan", 36),
+    ("white", 37),
+];
+
+pub fn record() -> Value {
+    Value::Record(
+        STYLES
+            .iter()
+            .map(|&(name, code)| {
+                let style = Value::builtin(Purity::Pure, move |interpreter, text, location| {
+                    let text = string_argument(interpreter, text, location, name)?;
+                    Ok(Value::String(if interpreter.colors_enabled() {
+                        format!("\x1b[{code}m{text}\x1b[0m")
+                    } else {
+                        text
+                    }))
+                });
+                (name.to_string(), style)
+            })
+            .collect::<BTreeMap<_, _>>(),
+    )
+}
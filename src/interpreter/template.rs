@@ -0,0 +1,176 @@
+use super::Interpreter;
+use super::builtins::string_argument;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Value};
+use std::rc::Rc;
+
+enum Node {
+    Text(String),
+    Variable {
+        name: String,
+        escape: bool,
+    },
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Self>,
+    },
+}
+
+pub fn render(
+    interpreter: &mut Interpreter,
+    template: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let template = string_argument(interpreter, template, location.clone(), "render")?;
+    let mut rest = template.as_str();
+    let nodes = parse(&mut rest, None)
+        .map_err(|message| Error::with_help(ErrorKind::InvalidArguments, location, message))?;
+
+    Ok(Value::builtin(
+        Purity::Pure,
+        move |interpreter, context, _| {
+            let context = interpreter.force(context)?;
+            let mut output = String::new();
+            render_nodes(&nodes, &mut vec![context], &mut output);
+            Ok(Value::String(output))
+        },
+    ))
+}
+
+fn parse(rest: &mut &str, section: Option<&str>) -> std::result::Result<Vec<Node>, String> {
+    let mut nodes = vec![];
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            nodes.push(Node::Text(rest[..start].to_string()));
+        }
+        *rest = &rest[start + 2..];
+
+        let (tag, escape) = if let Some(raw) = rest.strip_prefix('{') {
+            let end = raw.find("}}}").ok_or("Unclosed {{{ tag in template")?;
+            let tag = raw[..end].trim().to_string();
+            *rest = &raw[end + 3..];
+            (tag, false)
+        } else {
+            let end = rest.find("}}").ok_or("Unclosed {{ tag in template")?;
+            let tag = rest[..end].trim().to_string();
+            *rest = &rest[end + 2..];
+            (tag, true)
+        };
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            return match section {
+                Some(open) if open == name => Ok(nodes),
+                Some(open) => Err(format!(
+                    "Section {open} was closed by {{{{/{name}}}}} in template"
+                )),
+                None => Err(format!("Unopened section {name} closed in template")),
+            };
+        }
+        if let Some((inverted, name)) = tag
+            .strip_prefix('#')
+            .map(|name| (false, name))
+            .or_else(|| tag.strip_prefix('^').map(|name| (true, name)))
+        {
+            let name = name.trim().to_string();
+            let children = parse(rest, Some(&name))?;
+            nodes.push(Node::Section {
+                name,
+                inverted,
+                children,
+            });
+        } else if !tag.starts_with('!') {
+            nodes.push(Node::Variable { name: tag, escape });
+        }
+    }
+
+    if let Some(open) = section {
+        return Err(format!("Unclosed section {open} in template"));
+    }
+    if !rest.is_empty() {
+        nodes.push(Node::Text((*rest).to_string()));
+    }
+    *rest = "";
+    Ok(nodes)
+}
+
+fn render_nodes(nodes: &[Node], stack: &mut Vec<Value>, output: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => output.push_str(text),
+            Node::Variable { name, escape } => {
+                if let Some(value) = lookup(stack, name) {
+                    let text = value.to_string();
+                    if *escape {
+                        output.push_str(&escape_html(&text));
+                    } else {
+                        output.push_str(&text);
+                    }
+                }
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(stack, name).cloned();
+                match value {
+                    Some(Value::List(items)) if !*inverted => {
+                        for item in items {
+                            stack.push(item);
+                            render_nodes(children, stack, output);
+                            stack.pop();
+                        }
+                    }
+                    Some(value) if !*inverted && is_truthy(&value) => {
+                        stack.push(value);
+                        render_nodes(children, stack, output);
+                        stack.pop();
+                    }
+                    value if *inverted && !value.as_ref().is_some_and(is_truthy) => {
+                        render_nodes(children, stack, output);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+fn lookup<'a>(stack: &'a [Value], name: &str) -> Option<&'a Value> {
+    if name == "." {
+        return stack.last();
+    }
+
+    let mut parts = name.split('.');
+    let first = parts.next()?;
+    let mut value = stack.iter().rev().find_map(|context| match context {
+        Value::Record(fields) => fields.get(first),
+        _ => None,
+    })?;
+    for part in parts {
+        let Value::Record(fields) = value else {
+            return None;
+        };
+        value = fields.get(part)?;
+    }
+    Some(value)
+}
+
+const fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Boolean(b) => *b,
+        Value::None => false,
+        Value::List(items) => !items.is_empty(),
+        _ => true,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
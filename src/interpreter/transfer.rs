@@ -0,0 +1,191 @@
+use super::{Environment, Interpreter};
+use crate::STACK_SIZE;
+use crate::engine::Engine;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Transfer, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::thread;
+
+pub fn export(
+    interpreter: &mut Interpreter,
+    value: Value,
+    location: &Rc<Location>,
+) -> Result<Transfer> {
+    Ok(match interpreter.force(value)? {
+        Value::Boolean(boolean) => Transfer::Boolean(boolean),
+        Value::Float(float) => Transfer::Float(float),
+        Value::Integer(integer) => Transfer::Integer(integer),
+        Value::None => Transfer::None,
+        Value::String(string) => Transfer::String(string),
+        Value::List(elements) => Transfer::List(
+            elements
+                .into_iter()
+                .map(|element| export(interpreter, element, location))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Record(fields) => Transfer::Record(
+            fields
+                .into_iter()
+                .map(|(name, value)| Ok((name, export(interpreter, value, location)?)))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Tagged { tag, value } => Transfer::Tagged {
+            tag,
+            value: Box::new(export(interpreter, *value, location)?),
+        },
+        Value::Constructor { data, name, index } => Transfer::Constructor {
+            data: data.to_string(),
+            name,
+            index,
+        },
+        function => match global_name(&interpreter.globals(), &function) {
+            Some(name) => Transfer::Function(name),
+            None => {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location.clone(),
+                    "Only top-level functions can be sent to another interpreter, \
+                     this function is a local closure or a partial application",
+                );
+            }
+        },
+    })
+}
+
+pub fn import(
+    interpreter: &Interpreter,
+    transfer: Transfer,
+    location: &Rc<Location>,
+) -> Result<Value> {
+    Ok(match transfer {
+        Transfer::Boolean(boolean) => Value::Boolean(boolean),
+        Transfer::Float(float) => Value::Float(float),
+        Transfer::Integer(integer) => Value::Integer(integer),
+        Transfer::None => Value::None,
+        Transfer::String(string) => Value::String(string),
+        Transfer::List(elements) => Value::List(
+            elements
+                .into_iter()
+                .map(|element| import(interpreter, element, location))
+                .collect::<Result<_>>()?,
+        ),
+        Transfer::Record(fields) => Value::Record(
+            fields
+                .into_iter()
+                .map(|(name, value)| Ok((name, import(interpreter, value, location)?)))
+                .collect::<Result<_>>()?,
+        ),
+        Transfer::Tagged { tag, value } => Value::Tagged {
+            tag,
+            value: Box::new(import(interpreter, *value, location)?),
+        },
+        Transfer::Constructor { data, name, index } => Value::Constructor {
+            data: data.into(),
+            name,
+            index,
+        },
+        Transfer::Function(name) => {
+            let function = interpreter.globals().borrow().get(&name);
+            function.ok_or_else(|| {
+                Error::with_help(
+                    ErrorKind::UndeclaredFunction,
+                    location.clone(),
+                    format!("{name} is not defined in the receiving interpreter"),
+                )
+            })?
+        }
+    })
+}
+
+pub fn call(
+    interpreter: &mut Interpreter,
+    function: &str,
+    argument: Transfer,
+    location: &Rc<Location>,
+) -> Result<Transfer> {
+    let function = import(
+        interpreter,
+        Transfer::Function(function.to_string()),
+        location,
+    )?;
+    let argument = import(interpreter, argument, location)?;
+    let result = interpreter.apply(function, argument, location.clone())?;
+    export(interpreter, result, location)
+}
+
+pub fn isolate(
+    interpreter: &mut Interpreter,
+    function: Value,
+    location: Rc<Location>,
+) -> Result<Value> {
+    let Transfer::Function(name) = export(interpreter, function, &location)? else {
+        return err!(
+            ErrorKind::InvalidArguments,
+            location,
+            "isolate expects a top-level function",
+        );
+    };
+    let (file, source) = interpreter
+        .module_of(&name)
+        .map(|(file, source)| (file.map(ToOwned::to_owned), source.to_string()))
+        .unwrap_or_default();
+
+    Ok(Value::builtin(
+        Purity::Effectful,
+        move |interpreter, argument, location| {
+            let argument = export(interpreter, argument, &location)?;
+            let (file, source, name) = (file.clone(), source.clone(), name.clone());
+            let caller = (*location).clone();
+            let result = thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn(move || {
+                    Engine::new()
+                        .call(&source, file, &name, argument, caller)
+                        .map_err(|e| e.to_string())
+                })
+                .map_err(|e| e.to_string())
+                .and_then(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|_| Err("the isolated interpreter panicked".to_string()))
+                });
+            match result {
+                Ok(result) => import(interpreter, result, &location),
+                Err(e) => err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    format!("The isolated call failed: {e}"),
+                ),
+            }
+        },
+    ))
+}
+
+fn global_name(globals: &Rc<RefCell<Environment>>, value: &Value) -> Option<String> {
+    let mut environment = Some(globals.clone());
+    while let Some(current) = environment {
+        let name = current
+            .borrow()
+            .bindings()
+            .find(|(_, bound)| same_function(bound, value))
+            .map(|(name, _)| name.clone());
+        if name.is_some() {
+            return name;
+        }
+        environment = current.borrow().parent();
+    }
+    None
+}
+
+fn same_function(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Function { id: l, .. }, Value::Function { id: r, .. }) => l == r,
+        (
+            Value::BuiltinFunction { function: l, .. },
+            Value::BuiltinFunction { function: r, .. },
+        ) => Rc::ptr_eq(l, r),
+        _ => false,
+    }
+}
@@ -0,0 +1,123 @@
+use super::Interpreter;
+use super::builtins::string_argument;
+use crate::err;
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{Location, Purity, Value};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+pub fn record() -> Value {
+    Value::Record(BTreeMap::from([
+        ("get".to_string(), Value::builtin(Purity::Effectful, get)),
+        (
+            "expand".to_string(),
+            Value::builtin(Purity::Effectful, expand),
+        ),
+        ("load".to_string(), Value::builtin(Purity::Effectful, load)),
+    ]))
+}
+
+fn get(interpreter: &mut Interpreter, name: Value, location: Rc<Location>) -> Result<Value> {
+    let name = string_argument(interpreter, name, location, "env.get")?;
+    Ok(lookup(interpreter, &name).map_or(Value::None, Value::String))
+}
+
+fn expand(interpreter: &mut Interpreter, template: Value, location: Rc<Location>) -> Result<Value> {
+    let template = string_argument(interpreter, template, location.clone(), "env.expand")?;
+    expand_template(interpreter, &template, location).map(Value::String)
+}
+
+fn lookup(interpreter: &Interpreter, name: &str) -> Option<String> {
+    env::var(name)
+        .ok()
+        .or_else(|| interpreter.loaded_variable(name))
+}
+
+fn load(interpreter: &mut Interpreter, path: Value, location: Rc<Location>) -> Result<Value> {
+    let path = string_argument(interpreter, path, location.clone(), "env.load")?;
+    let path = interpreter
+        .current_file()
+        .and_then(Path::parent)
+        .map_or_else(
+            || Path::new(&path).to_path_buf(),
+            |directory| directory.join(&path),
+        );
+    let source = fs::read_to_string(&path).map_err(|e| {
+        Error::with_help(
+            ErrorKind::InvalidArguments,
+            location.clone(),
+            format!("Could not read {}: {e}", path.display()),
+        )
+    })?;
+
+    let mut variables = BTreeMap::new();
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            return err!(
+                ErrorKind::InvalidArguments,
+                location,
+                format!("{}:{}: expected NAME=value", path.display(), i + 1),
+            );
+        };
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|&quote| value.strip_prefix(quote)?.strip_suffix(quote))
+            .unwrap_or(value);
+        variables.insert(name.trim().to_string(), value.to_string());
+    }
+
+    interpreter.load_variables(variables.clone());
+    Ok(Value::Record(
+        variables
+            .into_iter()
+            .map(|(name, value)| (name, Value::String(value)))
+            .collect(),
+    ))
+}
+
+fn expand_template(
+    interpreter: &Interpreter,
+    template: &str,
+    location: Rc<Location>,
+) -> Result<String> {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            return err!(
+                ErrorKind::InvalidArguments,
+                location,
+                "Expected a } to close ${ in env.expand",
+            );
+        };
+        let reference = &rest[start + 2..start + end];
+        let (name, default) = reference
+            .split_once(":-")
+            .map_or((reference, None), |(name, default)| (name, Some(default)));
+        match lookup(interpreter, name).or_else(|| default.map(ToString::to_string)) {
+            Some(value) => output.push_str(&value),
+            None => {
+                return err!(
+                    ErrorKind::InvalidArguments,
+                    location,
+                    format!(
+                        "The environment variable {name} is not set, use ${{{name}:-default}} to fall back"
+                    ),
+                );
+            }
+        }
+        rest = &rest[start + end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
@@ -1,4 +1,3 @@
-use crate::err;
 use crate::error::{Error, ErrorKind, Result};
 use crate::model::{Located, LocatedExt, Location, Token, TokenKind, TokenValue};
 use std::rc::Rc;
@@ -28,7 +27,8 @@ impl Lexer {
                 self.advance();
                 continue;
             }
-            tokens.push(self.consume_token(c)?.at(self.location()));
+            let location = self.location();
+            tokens.push(self.consume_token(c)?.at(location));
             self.advance();
         }
         Ok(tokens)
@@ -70,7 +70,7 @@ impl Lexer {
                     self.bol = self.index + 1;
                     TokenKind::Newline
                 }
-                '{' => self.consume_comment(),
+                '{' => self.consume_comment()?,
                 '}' => TokenKind::RightBrace,
                 '[' => TokenKind::LeftBracket,
                 ']' => TokenKind::RightBracket,
@@ -85,21 +85,15 @@ impl Lexer {
                     }
                 }
                 '^' => TokenKind::Caret,
-                '|' => TokenKind::Pipe,
-                '+' => TokenKind::Plus,
-                '-' => {
-                    if self.consume('-') {
-                        while let Some(c) = self.current() {
-                            if c == '\n' {
-                                break;
-                            }
-                            self.advance();
-                        }
-                        TokenKind::Newline
+                '|' => {
+                    if self.consume('>') {
+                        TokenKind::PipeGreater
                     } else {
-                        TokenKind::Minus
+                        TokenKind::Pipe
                     }
                 }
+                '+' => TokenKind::Plus,
+                '-' => self.consume_minus()?,
                 '*' => {
                     if self.consume('*') {
                         TokenKind::StarStar
@@ -134,6 +128,7 @@ impl Lexer {
                 ':' => TokenKind::Colon,
                 ',' => TokenKind::Comma,
                 '$' => TokenKind::Dollar,
+                '\\' => TokenKind::Backslash,
                 '.' => TokenKind::Dot,
                 '#' => TokenKind::Hash,
                 '?' => TokenKind::QuestionMark,
@@ -154,30 +149,67 @@ impl Lexer {
         ))
     }
 
-    fn consume_comment(&mut self) -> TokenKind {
-        if self.consume('-') {
-            let mut nesting = 1;
-            loop {
-                match (self.current(), self.next(1)) {
-                    (Some('{'), Some('-')) => {
-                        nesting += 1;
-                        self.advance();
-                        self.advance();
-                    }
-                    (Some('-'), Some('}')) => {
-                        nesting -= 1;
-                        self.advance();
-                        self.advance();
+    fn consume_minus(&mut self) -> Result<TokenKind> {
+        if self.next(1) == Some('}') {
+            return Err(Error::with_help(
+                ErrorKind::UnexpectedToken,
+                self.location(),
+                "-} closes a block comment that was never opened with {-.",
+            ));
+        }
+        if self.consume('>') {
+            return Ok(TokenKind::Arrow);
+        }
+        if !self.consume('-') {
+            return Ok(TokenKind::Minus);
+        }
+        while let Some(c) = self.current() {
+            if c == '\n' {
+                self.row += 1;
+                self.bol = self.index + 1;
+                break;
+            }
+            self.advance();
+        }
+        Ok(TokenKind::Newline)
+    }
+
+    fn consume_comment(&mut self) -> Result<TokenKind> {
+        let start = self.location();
+        if !self.consume('-') {
+            return Ok(TokenKind::LeftBrace);
+        }
+
+        let mut nesting = 1;
+        loop {
+            match (self.current(), self.next(1)) {
+                (None, _) => {
+                    return Err(Error::with_help(
+                        ErrorKind::UnterminatedComment,
+                        start,
+                        "Expected a -} to close this block comment.",
+                    ));
+                }
+                (Some('{'), Some('-')) => {
+                    nesting += 1;
+                    self.advance();
+                    self.advance();
+                }
+                (Some('-'), Some('}')) => {
+                    nesting -= 1;
+                    self.advance();
+                    if nesting == 0 {
+                        return Ok(TokenKind::Newline);
                     }
-                    _ => self.advance(),
+                    self.advance();
                 }
-                if nesting == 0 {
-                    break;
+                (Some('\n'), _) => {
+                    self.row += 1;
+                    self.bol = self.index + 1;
+                    self.advance();
                 }
+                _ => self.advance(),
             }
-            TokenKind::Newline
-        } else {
-            TokenKind::LeftBrace
         }
     }
 
@@ -202,6 +234,13 @@ impl Lexer {
             "false" => Token::new(TokenKind::False, TokenValue::Boolean(false)),
             "null" => Token::new(TokenKind::Null, TokenValue::None),
             "use" => Token::new(TokenKind::Use, TokenValue::None),
+            "mutual" => Token::new(TokenKind::Mutual, TokenValue::None),
+            "end" => Token::new(TokenKind::End, TokenValue::None),
+            "newtype" => Token::new(TokenKind::Newtype, TokenValue::None),
+            "data" => Token::new(TokenKind::Data, TokenValue::None),
+            "let" => Token::new(TokenKind::Let, TokenValue::None),
+            "do" => Token::new(TokenKind::Do, TokenValue::None),
+            "in" => Token::new(TokenKind::In, TokenValue::None),
             _ => Token::new(TokenKind::Identifier, TokenValue::Identifier(identifier)),
         }
     }
@@ -209,48 +248,88 @@ impl Lexer {
     fn consume_number(&mut self) -> Result<Token> {
         let start = self.index;
 
-        while let Some(c) = self.next(1) {
-            if !c.is_ascii_digit() {
-                break;
-            }
+        let radix = match (self.source[start], self.next(1)) {
+            ('0', Some('x' | 'X')) => Some(16),
+            ('0', Some('b' | 'B')) => Some(2),
+            ('0', Some('o' | 'O')) => Some(8),
+            _ => None,
+        };
+        if let Some(radix) = radix {
             self.advance();
+            self.consume_digits(|c| c.is_ascii_alphanumeric());
+            self.reject_trailing(&['_'])?;
+
+            let digits = self.number_text(start + 2);
+            return Ok(Token::new(
+                TokenKind::Integer,
+                TokenValue::Integer(
+                    i128::from_str_radix(&digits, radix)
+                        .map_err(|_| Error::new(ErrorKind::NotANumber, self.location()))?,
+                ),
+            ));
         }
 
-        if self.consume('.') {
-            match self.next(2) {
-                Some(c) if c.is_ascii_digit() => {
-                    while let Some(c) = self.next(1) {
-                        if !c.is_ascii_digit() {
-                            break;
-                        }
-                        self.advance();
-                    }
+        self.consume_digits(|c| c.is_ascii_digit());
 
-                    let number = self.source[start..=self.index].iter().collect::<String>();
+        let fraction =
+            self.next(1) == Some('.') && self.next(2).is_some_and(|c| c.is_ascii_digit());
+        if fraction {
+            self.advance();
+            self.consume_digits(|c| c.is_ascii_digit());
+        }
+        let digits_at = if matches!(self.next(2), Some('+' | '-')) {
+            3
+        } else {
+            2
+        };
+        let exponent = matches!(self.next(1), Some('e' | 'E'))
+            && self.next(digits_at).is_some_and(|c| c.is_ascii_digit());
+        if exponent {
+            for _ in 1..digits_at {
+                self.advance();
+            }
+            self.consume_digits(|c| c.is_ascii_digit());
+        }
+        self.reject_trailing(&['_', 'e', 'E'])?;
 
-                    return Ok(Token::new(
-                        TokenKind::Float,
-                        TokenValue::Float(
-                            number
-                                .parse()
-                                .map_err(|_| Error::new(ErrorKind::NotANumber, self.location()))?,
-                        ),
-                    ));
-                }
-                _ => (),
+        let number = self.number_text(start);
+        let not_a_number = || Error::new(ErrorKind::NotANumber, self.location());
+        Ok(if fraction || exponent {
+            Token::new(
+                TokenKind::Float,
+                TokenValue::Float(number.parse().map_err(|_| not_a_number())?),
+            )
+        } else {
+            Token::new(
+                TokenKind::Integer,
+                TokenValue::Integer(number.parse().map_err(|_| not_a_number())?),
+            )
+        })
+    }
+
+    fn consume_digits(&mut self, is_digit: impl Fn(char) -> bool) {
+        while let Some(c) = self.next(1) {
+            if is_digit(c) || (c == '_' && self.next(2).is_some_and(&is_digit)) {
+                self.advance();
+            } else {
+                break;
             }
         }
+    }
 
-        let number = self.source[start..=self.index].iter().collect::<String>();
+    fn reject_trailing(&mut self, characters: &[char]) -> Result<()> {
+        if self.next(1).is_some_and(|c| characters.contains(&c)) {
+            self.advance();
+            return Err(Error::new(ErrorKind::NotANumber, self.location()));
+        }
+        Ok(())
+    }
 
-        Ok(Token::new(
-            TokenKind::Integer,
-            TokenValue::Integer(
-                number
-                    .parse()
-                    .map_err(|_| Error::new(ErrorKind::NotANumber, self.location()))?,
-            ),
-        ))
+    fn number_text(&self, start: usize) -> String {
+        self.source[start..=self.index]
+            .iter()
+            .filter(|&&c| c != '_')
+            .collect()
     }
 
     fn consume_string(&mut self) -> Result<Token> {
@@ -264,10 +343,15 @@ impl Lexer {
                 ));
             }
         }
-        err!(
+        let end = Rc::new(Location {
+            row: self.row,
+            column: self.index + 1 - self.bol,
+        });
+        Err(Error::with_help(
             ErrorKind::UnterminatedString,
             self.location(),
             "Expected a \" after this string.",
         )
+        .suggest(end, 0, "\""))
     }
 }
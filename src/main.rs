@@ -1,88 +1,28 @@
 #![macro_use]
+mod checker;
+mod cli;
+mod engine;
 mod error;
+mod explain;
 mod interpreter;
 mod lexer;
 mod model;
 mod parser;
+mod resolver;
 
-use error::Result;
-use interpreter::{Environment, Interpreter};
-use lexer::Lexer;
-use parser::Parser;
-use std::{
-    fs::{self, read_dir},
-    process::ExitCode,
-};
+use std::{env, process::ExitCode, thread};
 
-fn main() -> ExitCode {
-    test()
-}
-
-fn test() -> ExitCode {
-    let tests = match read_dir("tests") {
-        Ok(entries) => entries,
-        Err(e) => {
-            eprintln!("Error: Failed to read the tests directory: {e}");
-            return ExitCode::FAILURE;
-        }
-    };
-
-    let mut failed_tests = Vec::new();
-
-    for test in tests {
-        let path = match test {
-            Ok(entry) => entry.path(),
-            Err(e) => {
-                eprintln!("Error while instantiating test: {e}");
-                return ExitCode::FAILURE;
-            }
-        };
-        if path.is_file() {
-            match fs::read_to_string(&path) {
-                Ok(content) => match run(&content, Some(path.clone())) {
-                    Ok(()) => println!(
-                        "\x1b[32mSUCCESS\x1b[0m {} completed successfully.",
-                        path.display()
-                    ),
-                    Err(e) => {
-                        eprintln!(
-                            "\x1b[31mFAILED\x1b[0m {} failed with error: {e}",
-                            path.display()
-                        );
-                        failed_tests.push(path.display().to_string());
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Failed to load test {}: {e}", path.display());
-                    return ExitCode::FAILURE;
-                }
-            }
-        }
-    }
-
-    if !failed_tests.is_empty() {
-        eprintln!("The following tests failed:");
-        for test in failed_tests {
-            eprintln!("- {test}");
-        }
-        return ExitCode::FAILURE;
-    }
-    ExitCode::SUCCESS
-}
-
-use std::path::PathBuf;
-
-fn run(source: &str, file_path: Option<PathBuf>) -> Result<()> {
-    let mut lexer = Lexer::new();
-    let tokens = lexer.lex(source)?;
+pub const STACK_SIZE: usize = 1 << 30;
 
-    let mut parser = Parser::new();
-    let ast = parser.parse(tokens)?;
-
-    let environment = Environment::new();
-    environment.borrow_mut().add_builtins();
-    let mut interpreter = Interpreter::with_file(environment, file_path);
-    interpreter.interpret(ast)?;
-
-    Ok(())
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || cli::run(&args))
+        .and_then(|interpreter| {
+            interpreter
+                .join()
+                .map_err(|_| std::io::Error::other("panic"))
+        })
+        .unwrap_or(ExitCode::FAILURE)
 }
@@ -0,0 +1,16 @@
+use super::{Located, Token};
+
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    pub name: Located<Token>,
+    pub arguments: Vec<Located<Token>>,
+}
+
+impl Attribute {
+    pub fn is(&self, name: &str) -> bool {
+        self.name
+            .node
+            .get_identifier_name()
+            .is_some_and(|n| n == name)
+    }
+}
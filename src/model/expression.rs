@@ -1,34 +1,65 @@
-use super::{Located, Token, located::LocatedExt};
+use super::{Located, Statement, Token, located::LocatedExt};
 
 #[derive(Clone, Debug)]
 pub enum Expression {
     Unary {
         operator: Located<Token>,
-        expression: Box<Located<Expression>>,
+        expression: Box<Located<Self>>,
     },
     Binary {
-        left: Box<Located<Expression>>,
+        left: Box<Located<Self>>,
         operator: Located<Token>,
-        right: Box<Located<Expression>>,
+        right: Box<Located<Self>>,
+    },
+    Block {
+        statements: Vec<Located<Statement>>,
     },
     Call {
-        function: Box<Located<Expression>>,
-        argument: Box<Located<Expression>>,
+        function: Box<Located<Self>>,
+        argument: Box<Located<Self>>,
     },
     Identifier {
         token: Located<Token>,
     },
     If {
-        branches: Vec<(Located<Expression>, Located<Expression>)>,
-        otherwise: Box<Located<Expression>>,
+        branches: Vec<(Located<Self>, Located<Self>)>,
+        otherwise: Box<Located<Self>>,
     },
     Lambda {
         parameter: Located<Token>,
-        body: Box<Located<Expression>>,
+        body: Box<Located<Self>>,
+    },
+    Let {
+        name: Located<Token>,
+        value: Box<Located<Self>>,
+        body: Box<Located<Self>>,
+    },
+    List {
+        elements: Vec<Located<Self>>,
+    },
+    Record {
+        fields: Vec<(Located<Token>, Located<Self>)>,
+    },
+    Field {
+        record: Box<Located<Self>>,
+        field: Located<Token>,
     },
     Literal {
         token: Located<Token>,
     },
+    Match {
+        arguments: Vec<Located<Token>>,
+        clauses: Vec<(Vec<Located<Token>>, Located<Self>)>,
+    },
+}
+
+impl Expression {
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::Lambda { body, .. } => 1 + body.node.arity(),
+            _ => 0,
+        }
+    }
 }
 
 impl LocatedExt<Self> for Expression {
@@ -1,18 +1,26 @@
+pub mod attribute;
 pub mod expression;
 pub mod located;
 pub mod location;
 pub mod statement;
+pub mod tag;
 pub mod token;
 pub mod token_kind;
 pub mod token_value;
+pub mod transfer;
 pub mod value;
+pub mod value_key;
 
+pub use attribute::Attribute;
 pub use expression::Expression;
 pub use located::Located;
 pub use located::LocatedExt;
 pub use location::Location;
 pub use statement::Statement;
+pub use tag::Tag;
 pub use token::Token;
 pub use token_kind::TokenKind;
 pub use token_value::TokenValue;
-pub use value::Value;
+pub use transfer::Transfer;
+pub use value::{Purity, Value};
+pub use value_key::ValueKey;
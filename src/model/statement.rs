@@ -1,11 +1,19 @@
-use super::{Expression, Located, Token, located::LocatedExt};
+use super::{Attribute, Expression, Located, Token, located::LocatedExt};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Statement {
+    Attributed {
+        attributes: Vec<Attribute>,
+        statement: Box<Located<Self>>,
+    },
     Declaration {
         name: Located<Token>,
         types: Vec<Located<Token>>,
     },
+    Data {
+        name: Located<Token>,
+        constructors: Vec<Located<Token>>,
+    },
     Definition {
         name: Located<Token>,
         parameter: Located<Token>,
@@ -14,6 +22,15 @@ pub enum Statement {
     Expression {
         expression: Located<Expression>,
     },
+    Mutual {
+        definitions: Vec<Located<Self>>,
+    },
+    Newtype {
+        name: Located<Token>,
+    },
+    Pragma {
+        name: Located<Token>,
+    },
     Use {
         path: Vec<Located<Token>>,
     },
@@ -23,6 +40,25 @@ pub enum Statement {
     },
 }
 
+impl Statement {
+    pub fn defined_name(&self) -> Option<&Located<Token>> {
+        match self {
+            Self::Attributed { statement, .. } => statement.node.defined_name(),
+            Self::Definition { name, .. } | Self::Variable { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::Attributed { statement, .. } => statement.node.arity(),
+            Self::Definition { body, .. } => 1 + body.node.arity(),
+            Self::Variable { body, .. } => body.node.arity(),
+            _ => 0,
+        }
+    }
+}
+
 impl LocatedExt<Self> for Statement {
     fn at(self, location: std::rc::Rc<super::Location>) -> super::Located<Self> {
         Located {
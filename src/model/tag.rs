@@ -0,0 +1,25 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Tag {
+    Builtin(&'static str),
+    Newtype {
+        name: String,
+        module: Option<PathBuf>,
+    },
+}
+
+impl Tag {
+    pub const CONTINUE: Self = Self::Builtin("Continue");
+    pub const DONE: Self = Self::Builtin("Done");
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Builtin(name) => write!(f, "{name}"),
+            Self::Newtype { name, .. } => write!(f, "{name}"),
+        }
+    }
+}
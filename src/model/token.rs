@@ -15,7 +15,7 @@ pub struct Token {
 }
 
 impl Token {
-    pub fn new(kind: TokenKind, value: TokenValue) -> Self {
+    pub const fn new(kind: TokenKind, value: TokenValue) -> Self {
         Self { kind, value }
     }
 }
@@ -58,7 +58,7 @@ impl TryFrom<&Located<Token>> for String {
 impl Token {
     pub fn get_identifier_name(&self) -> Option<String> {
         if let TokenValue::Identifier(name) = &self.value {
-            Some(name.to_string())
+            Some(name.clone())
         } else {
             None
         }
@@ -15,6 +15,7 @@ pub enum TokenKind {
     Ampersand,
     Caret,
     Pipe,
+    PipeGreater,
     Bang,
     BangEqual,
     Equal,
@@ -27,6 +28,8 @@ pub enum TokenKind {
     Colon,
     Comma,
     Dollar,
+    Backslash,
+    Arrow,
     Dot,
     Hash,
     Newline,
@@ -41,6 +44,13 @@ pub enum TokenKind {
     False,
     Null,
     Use,
+    Mutual,
+    Newtype,
+    Data,
+    Let,
+    In,
+    Do,
+    End,
     Identifier,
     Float,
     Integer,
@@ -55,6 +65,8 @@ impl TokenKind {
             Self::True
                 | Self::False
                 | Self::LeftParenthesis
+                | Self::LeftBracket
+                | Self::LeftBrace
                 | Self::Null
                 | Self::Identifier
                 | Self::Integer
@@ -62,12 +74,27 @@ impl TokenKind {
         )
     }
 
+    pub const fn is_pattern(self) -> bool {
+        matches!(
+            self,
+            Self::Identifier
+                | Self::Underscore
+                | Self::Integer
+                | Self::Float
+                | Self::String
+                | Self::True
+                | Self::False
+                | Self::Null
+        )
+    }
+
     pub const fn is_operator(self) -> bool {
         matches!(
             self,
             Self::Ampersand
                 | Self::Caret
                 | Self::Pipe
+                | Self::PipeGreater
                 | Self::Plus
                 | Self::Minus
                 | Self::Star
@@ -87,6 +114,25 @@ impl TokenKind {
         )
     }
 
+    pub const fn is_ordering(self) -> bool {
+        matches!(
+            self,
+            Self::Greater | Self::GreaterEqual | Self::Less | Self::LessEqual
+        )
+    }
+
+    pub const fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            Self::BangEqual
+                | Self::EqualEqual
+                | Self::Greater
+                | Self::GreaterEqual
+                | Self::Less
+                | Self::LessEqual
+        )
+    }
+
     pub const fn can_start_expression(self) -> bool {
         matches!(
             self,
@@ -94,13 +140,19 @@ impl TokenKind {
                 | Self::Bang
                 | Self::Minus
                 | Self::LeftParenthesis
+                | Self::LeftBracket
+                | Self::LeftBrace
                 | Self::True
                 | Self::False
                 | Self::Null
                 | Self::Float
                 | Self::Integer
                 | Self::String
+                | Self::Underscore
                 | Self::If
+                | Self::Let
+                | Self::Do
+                | Self::Backslash
         )
     }
 }
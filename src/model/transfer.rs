@@ -0,0 +1,23 @@
+use super::Tag;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transfer {
+    Boolean(bool),
+    Float(f64),
+    Integer(i128),
+    None,
+    String(String),
+    List(Vec<Self>),
+    Record(BTreeMap<String, Self>),
+    Tagged {
+        tag: Tag,
+        value: Box<Self>,
+    },
+    Constructor {
+        data: String,
+        name: String,
+        index: usize,
+    },
+    Function(String),
+}
@@ -1,12 +1,22 @@
-use super::{Located, Location};
+use super::{Located, Location, Tag};
 use crate::err;
 use crate::error::{ErrorKind, Result};
-use crate::interpreter::environment::Environment;
+use crate::interpreter::{Environment, Interpreter};
 use crate::model::Expression;
 use crate::model::{Token, TokenValue};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Display, Formatter};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub type Builtin = dyn Fn(&mut Interpreter, Value, Rc<Location>) -> Result<Value>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Purity {
+    Pure,
+    Effectful,
+}
 
 #[derive(Clone)]
 pub enum Value {
@@ -15,13 +25,26 @@ pub enum Value {
     Integer(i128),
     None,
     String(String),
+    List(Vec<Self>),
+    Record(BTreeMap<String, Self>),
+    Tagged {
+        tag: Tag,
+        value: Box<Self>,
+    },
+    Constructor {
+        data: Rc<str>,
+        name: String,
+        index: usize,
+    },
     Function {
+        id: usize,
         parameter: Located<Token>,
         body: Located<Expression>,
         environment: Rc<RefCell<Environment>>,
     },
     BuiltinFunction {
-        function: Rc<dyn Fn(Value, Rc<Location>) -> Result<Value>>,
+        function: Rc<Builtin>,
+        purity: Purity,
     },
     Thunk {
         expression: Located<Expression>,
@@ -29,6 +52,43 @@ pub enum Value {
     },
 }
 
+impl Value {
+    pub fn function(
+        parameter: Located<Token>,
+        body: Located<Expression>,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Self {
+        static FUNCTIONS: AtomicUsize = AtomicUsize::new(0);
+        Self::Function {
+            id: FUNCTIONS.fetch_add(1, Ordering::Relaxed),
+            parameter,
+            body,
+            environment,
+        }
+    }
+
+    pub fn builtin(
+        purity: Purity,
+        function: impl Fn(&mut Interpreter, Self, Rc<Location>) -> Result<Self> + 'static,
+    ) -> Self {
+        Self::BuiltinFunction {
+            function: Rc::new(function),
+            purity,
+        }
+    }
+
+    pub const fn is_function(&self) -> bool {
+        matches!(self, Self::Function { .. } | Self::BuiltinFunction { .. })
+    }
+
+    pub const fn purity(&self) -> Purity {
+        match self {
+            Self::BuiltinFunction { purity, .. } => *purity,
+            _ => Purity::Pure,
+        }
+    }
+}
+
 impl TryFrom<&Located<Token>> for Value {
     type Error = crate::error::Error;
     fn try_from(value: &Located<Token>) -> Result<Self> {
@@ -55,13 +115,17 @@ impl Debug for Value {
             Self::Integer(i) => write!(f, "Integer({i:?})"),
             Self::None => write!(f, "None"),
             Self::String(s) => write!(f, "String({s:?})"),
+            Self::List(elements) => write!(f, "List({elements:?})"),
+            Self::Record(fields) => write!(f, "Record({fields:?})"),
+            Self::Tagged { tag, value } => write!(f, "Tagged({tag}, {value:?})"),
+            Self::Constructor { data, name, .. } => write!(f, "Constructor({data}.{name})"),
             Self::Function {
                 parameter, body, ..
             } => write!(
                 f,
                 "Function {{ parameter: {parameter:?}, body: {body:?}, ... }}"
             ),
-            Self::BuiltinFunction { .. } => write!(f, "BuiltinFunction"),
+            Self::BuiltinFunction { purity, .. } => write!(f, "BuiltinFunction({purity:?})"),
             Self::Thunk { expression, .. } => {
                 write!(f, "Thunk {{ expression: {expression:?}, ... }}")
             }
@@ -77,6 +141,29 @@ impl Display for Value {
             Self::Integer(i) => write!(f, "{i}"),
             Self::None => write!(f, "None"),
             Self::String(s) => write!(f, "{s}"),
+            Self::List(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Record(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Self::Record(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {name}: {value}")?;
+                }
+                write!(f, " }}")
+            }
+            Self::Tagged { tag, value } => write!(f, "{tag} {value}"),
+            Self::Constructor { name, .. } => write!(f, "{name}"),
             Self::Function {
                 parameter, body, ..
             } => write!(
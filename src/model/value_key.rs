@@ -0,0 +1,44 @@
+use super::{Tag, Value};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ValueKey {
+    Boolean(bool),
+    Float(u64),
+    Integer(i128),
+    None,
+    String(String),
+    List(Vec<Self>),
+    Record(Vec<(String, Self)>),
+    Tagged(Tag, Box<Self>),
+    Constructor(String, usize),
+}
+
+impl ValueKey {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Boolean(b) => Some(Self::Boolean(*b)),
+            Value::Float(f) => Some(Self::Float(f.to_bits())),
+            Value::Integer(i) => Some(Self::Integer(*i)),
+            Value::None => Some(Self::None),
+            Value::String(s) => Some(Self::String(s.clone())),
+            Value::List(elements) => elements
+                .iter()
+                .map(Self::from_value)
+                .collect::<Option<_>>()
+                .map(Self::List),
+            Value::Record(fields) => fields
+                .iter()
+                .map(|(name, value)| Some((name.clone(), Self::from_value(value)?)))
+                .collect::<Option<_>>()
+                .map(Self::Record),
+            Value::Tagged { tag, value } => Some(Self::Tagged(
+                tag.clone(),
+                Box::new(Self::from_value(value)?),
+            )),
+            Value::Constructor { data, index, .. } => {
+                Some(Self::Constructor(data.to_string(), *index))
+            }
+            Value::Function { .. } | Value::BuiltinFunction { .. } | Value::Thunk { .. } => None,
+        }
+    }
+}
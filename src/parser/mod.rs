@@ -3,8 +3,10 @@ pub mod precedence;
 pub use precedence::Precedence;
 
 use crate::err;
-use crate::error::{ErrorKind, Result};
-use crate::model::{Expression, Located, LocatedExt, Location, Statement, Token, TokenKind};
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::{
+    Attribute, Expression, Located, LocatedExt, Location, Statement, Token, TokenKind, TokenValue,
+};
 use std::rc::Rc;
 
 macro_rules! try_consume_any {
@@ -31,11 +33,26 @@ macro_rules! consume {
         }
         $self.advance();
     };
+    ($self:expr, $kind:path, $loc:expr, $insertion:expr) => {
+        if !$self.current_is($kind) {
+            let error = Error::with_help(
+                ErrorKind::ExpectedExpression,
+                $loc,
+                format!("Expected {:?}, found {:?}", $kind, $self.current()),
+            );
+            return Err(match $self.current() {
+                Some(token) => error.suggest(token.location, 0, $insertion),
+                None => error,
+            });
+        }
+        $self.advance();
+    };
 }
 
 pub struct Parser {
     tokens: Vec<Located<Token>>,
     index: usize,
+    guarded: bool,
 }
 
 impl Parser {
@@ -43,39 +60,65 @@ impl Parser {
         Self {
             tokens: vec![],
             index: 0,
+            guarded: false,
         }
     }
 
     pub fn parse(&mut self, tokens: Vec<Located<Token>>) -> Result<Vec<Located<Statement>>> {
+        let (statements, errors) = self.parse_with_errors(tokens);
+        errors.into_iter().next().map_or(Ok(statements), Err)
+    }
+
+    pub fn parse_with_errors(
+        &mut self,
+        tokens: Vec<Located<Token>>,
+    ) -> (Vec<Located<Statement>>, Vec<Error>) {
         self.tokens = tokens;
+        self.index = 0;
         let mut statements = vec![];
+        let mut errors = vec![];
 
         while !self.is_eof() {
             if try_consume_any!(*self, TokenKind::Newline) {
                 continue;
             }
 
-            if self.is_eof() {
-                break;
+            match self.parse_terminated_statement() {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    errors.push(error);
+                    self.synchronize();
+                }
             }
+        }
+        (statements, errors)
+    }
 
-            let statement = self.parse_statement()?;
+    fn parse_terminated_statement(&mut self) -> Result<Located<Statement>> {
+        let statement = self.parse_statement()?;
 
-            if !self.is_eof() && !try_consume_any!(self, TokenKind::Newline, TokenKind::Semicolon) {
-                let location = self
-                    .current()
-                    .ok_or(ErrorKind::UnexpectedEndOfFile)?
-                    .location;
-                return err!(
-                    ErrorKind::UnexpectedToken,
-                    location,
-                    "Expected a newline or semicolon."
-                );
-            }
+        if !self.is_eof() && !try_consume_any!(self, TokenKind::Newline, TokenKind::Semicolon) {
+            let location = self
+                .current()
+                .ok_or(ErrorKind::UnexpectedEndOfFile)?
+                .location;
+            return err!(
+                ErrorKind::UnexpectedToken,
+                location,
+                "Expected a newline or semicolon."
+            );
+        }
+
+        Ok(statement)
+    }
 
-            statements.push(statement);
+    fn synchronize(&mut self) {
+        while let Some(token) = self.current() {
+            self.advance();
+            if matches!(token.node.kind, TokenKind::Newline | TokenKind::Semicolon) {
+                break;
+            }
         }
-        Ok(statements)
     }
 
     fn current(&self) -> Option<Located<Token>> {
@@ -98,7 +141,7 @@ impl Parser {
         self.next(n).is_some_and(|t| t.node.kind == kind)
     }
 
-    fn is_eof(&self) -> bool {
+    const fn is_eof(&self) -> bool {
         self.index >= self.tokens.len()
     }
 
@@ -108,8 +151,280 @@ impl Parser {
             || self.current_is(TokenKind::Semicolon)
     }
 
+    fn continues_on_next_line(&self, kind: TokenKind) -> bool {
+        self.current_is(TokenKind::Newline)
+            && self.tokens[self.index..]
+                .iter()
+                .find(|t| t.node.kind != TokenKind::Newline)
+                .is_some_and(|t| t.node.kind == kind)
+    }
+
+    fn is_definition(&self, index: usize) -> bool {
+        let mut tokens = self.tokens[index..]
+            .iter()
+            .map(|t| t.node.kind)
+            .skip_while(|kind| kind.is_pattern())
+            .peekable();
+        if tokens.next_if_eq(&TokenKind::Equal).is_some() {
+            return true;
+        }
+        let mut tokens = tokens.skip_while(|kind| *kind == TokenKind::Newline);
+        tokens.next() == Some(TokenKind::Pipe)
+            && tokens
+                .take_while(|kind| *kind != TokenKind::Newline)
+                .any(|kind| kind == TokenKind::Equal)
+    }
+
+    fn is_declaration(&self, index: usize) -> bool {
+        let mut tokens = self.tokens[index..]
+            .iter()
+            .take_while(|t| t.node.kind != TokenKind::Newline);
+        tokens
+            .next()
+            .is_some_and(|t| t.node.kind == TokenKind::Identifier)
+            && tokens
+                .next()
+                .is_some_and(|t| t.node.kind == TokenKind::Colon)
+            && tokens.clone().next().is_some()
+            && tokens.all(|t| {
+                t.node.kind == TokenKind::Underscore
+                    || t.node
+                        .get_identifier_name()
+                        .is_some_and(|name| name.starts_with(char::is_uppercase))
+            })
+    }
+
+    fn continues_definition_of(&self, name: &Located<Token>) -> bool {
+        self.current_is(TokenKind::Newline)
+            && (self.index..self.tokens.len())
+                .find(|&index| self.tokens[index].node.kind != TokenKind::Newline)
+                .is_some_and(|index| {
+                    self.tokens[index].node.value == name.node.value && self.is_definition(index)
+                })
+    }
+
     fn parse_statement(&mut self) -> Result<Located<Statement>> {
-        self.parse_use()
+        self.parse_pragma()
+    }
+
+    fn parse_pragma(&mut self) -> Result<Located<Statement>> {
+        let location = self
+            .current()
+            .ok_or(ErrorKind::UnexpectedEndOfFile)?
+            .location;
+
+        if !try_consume_any!(self, TokenKind::Hash) {
+            return self.parse_attributed();
+        }
+
+        let name = self.expect_identifier("Expected a pragma name after #.")?;
+        if name.node.get_identifier_name().as_deref() != Some("strict") {
+            return err!(
+                ErrorKind::UnexpectedToken,
+                name.location,
+                "Unknown pragma, the only supported pragma is #strict."
+            );
+        }
+        Ok(Statement::Pragma { name }.at(location))
+    }
+
+    fn parse_attributed(&mut self) -> Result<Located<Statement>> {
+        let location = self
+            .current()
+            .ok_or(ErrorKind::UnexpectedEndOfFile)?
+            .location;
+
+        let mut attributes = vec![];
+        while try_consume_any!(self, TokenKind::At) {
+            attributes.push(self.parse_attribute()?);
+            while try_consume_any!(self, TokenKind::Newline) {}
+        }
+
+        if attributes.is_empty() {
+            return self.parse_mutual();
+        }
+
+        let statement = self.parse_mutual()?;
+        if !matches!(
+            statement.node,
+            Statement::Data { .. } | Statement::Definition { .. } | Statement::Variable { .. }
+        ) {
+            return err!(
+                ErrorKind::UnexpectedToken,
+                statement.location,
+                "Attributes can only be attached to definitions and data declarations."
+            );
+        }
+
+        Ok(Statement::Attributed {
+            attributes,
+            statement: Box::new(statement),
+        }
+        .at(location))
+    }
+
+    fn parse_attribute(&mut self) -> Result<Attribute> {
+        let name = self.expect_identifier("Expected an attribute name after @.")?;
+
+        let mut arguments = vec![];
+        if try_consume_any!(self, TokenKind::LeftParenthesis) {
+            if !try_consume_any!(self, TokenKind::RightParenthesis) {
+                loop {
+                    arguments.push(self.expect_attribute_argument()?);
+                    if !try_consume_any!(self, TokenKind::Comma) {
+                        break;
+                    }
+                }
+                consume!(self, TokenKind::RightParenthesis, name.location, ")");
+            }
+        } else if self.current_is(TokenKind::String) {
+            arguments.push(self.expect_attribute_argument()?);
+        }
+
+        Ok(Attribute { name, arguments })
+    }
+
+    fn expect_attribute_argument(&mut self) -> Result<Located<Token>> {
+        let token = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
+        if !matches!(
+            token.node.kind,
+            TokenKind::String
+                | TokenKind::Integer
+                | TokenKind::Float
+                | TokenKind::True
+                | TokenKind::False
+                | TokenKind::Identifier
+        ) {
+            return err!(
+                ErrorKind::UnexpectedToken,
+                token.location,
+                "Attribute arguments must be literals or identifiers."
+            );
+        }
+        self.advance();
+        Ok(token)
+    }
+
+    fn parse_mutual(&mut self) -> Result<Located<Statement>> {
+        let location = self
+            .current()
+            .ok_or(ErrorKind::UnexpectedEndOfFile)?
+            .location;
+
+        if !self.current_is(TokenKind::Mutual) {
+            return self.parse_newtype();
+        }
+        self.advance();
+
+        let definitions = self.parse_sequence(&location, "mutual block", |parser| {
+            let definition = parser.parse_statement()?;
+            if !matches!(
+                definition.node,
+                Statement::Declaration { .. }
+                    | Statement::Definition { .. }
+                    | Statement::Variable { .. }
+            ) {
+                return err!(
+                    ErrorKind::UnexpectedToken,
+                    definition.location,
+                    "Only definitions are allowed inside a mutual block."
+                );
+            }
+            Ok(definition)
+        })?;
+
+        Ok(Statement::Mutual { definitions }.at(location))
+    }
+
+    fn parse_sequence<T>(
+        &mut self,
+        location: &Rc<Location>,
+        block: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut items = vec![];
+        loop {
+            while try_consume_any!(self, TokenKind::Newline, TokenKind::Semicolon) {}
+            if try_consume_any!(self, TokenKind::End) {
+                return Ok(items);
+            }
+            if self.is_eof() {
+                return err!(
+                    ErrorKind::UnexpectedEndOfFile,
+                    location.clone(),
+                    format!("Expected end to close this {block}.")
+                );
+            }
+
+            items.push(item(self)?);
+            if let Some(token) = self.current()
+                && !matches!(
+                    token.node.kind,
+                    TokenKind::Newline | TokenKind::Semicolon | TokenKind::End
+                )
+            {
+                return err!(
+                    ErrorKind::UnexpectedToken,
+                    token.location,
+                    format!("Expected a newline, semicolon or end in this {block}.")
+                );
+            }
+        }
+    }
+
+    fn parse_newtype(&mut self) -> Result<Located<Statement>> {
+        let location = self
+            .current()
+            .ok_or(ErrorKind::UnexpectedEndOfFile)?
+            .location;
+
+        if !self.current_is(TokenKind::Newtype) {
+            return self.parse_data();
+        }
+        self.advance();
+
+        let name = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
+        if name.node.kind != TokenKind::Identifier {
+            return err!(
+                ErrorKind::ExpectedExpression,
+                name.location,
+                "Expected a name after newtype."
+            );
+        }
+        self.advance();
+
+        Ok(Statement::Newtype { name }.at(location))
+    }
+
+    fn parse_data(&mut self) -> Result<Located<Statement>> {
+        let location = self
+            .current()
+            .ok_or(ErrorKind::UnexpectedEndOfFile)?
+            .location;
+
+        if !self.current_is(TokenKind::Data) {
+            return self.parse_use();
+        }
+        self.advance();
+
+        let name = self.expect_identifier("Expected a name after data.")?;
+        consume!(self, TokenKind::Equal, location, "= ");
+
+        let mut constructors = vec![self.expect_identifier("Expected a constructor name.")?];
+        while try_consume_any!(self, TokenKind::Pipe) {
+            constructors.push(self.expect_identifier("Expected a constructor name after |.")?);
+        }
+
+        Ok(Statement::Data { name, constructors }.at(location))
+    }
+
+    fn expect_identifier(&mut self, help: &str) -> Result<Located<Token>> {
+        let token = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
+        if token.node.kind != TokenKind::Identifier {
+            return err!(ErrorKind::ExpectedExpression, token.location, help);
+        }
+        self.advance();
+        Ok(token)
     }
 
     fn parse_use(&mut self) -> Result<Located<Statement>> {
@@ -153,72 +468,159 @@ impl Parser {
     }
 
     fn parse_declaration(&mut self) -> Result<Located<Statement>> {
-        if !self.next_is(1, TokenKind::Colon) {
+        if !self.is_declaration(self.index) {
             return self.parse_definition();
         }
 
         let name = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
         let location = name.location.clone();
-
-        match name.node.kind {
-            TokenKind::Identifier => {
-                self.advance();
-                self.advance();
-                let mut types = vec![];
-                while self.current_is(TokenKind::Underscore)
-                    || self.current_is(TokenKind::Identifier)
-                {
-                    let token = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
-                    self.advance();
-                    types.push(token);
-                }
-                Ok(Statement::Declaration { name, types }.at(location))
-            }
-            _ => err!(
-                ErrorKind::ExpectedExpression,
-                location,
-                "This should be an identifier.",
-            ),
+        self.advance();
+        self.advance();
+        let mut types = vec![];
+        while self.current_is(TokenKind::Underscore) || self.current_is(TokenKind::Identifier) {
+            let token = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
+            self.advance();
+            types.push(token);
         }
+        Ok(Statement::Declaration { name, types }.at(location))
     }
 
     fn parse_definition(&mut self) -> Result<Located<Statement>> {
-        if !self.current_is(TokenKind::Identifier)
-            || self.tokens[self.index..]
-                .iter()
-                .find(|t| !t.node.kind.is_primary())
-                .is_none_or(|t| t.node.kind != TokenKind::Equal)
-        {
+        if !self.current_is(TokenKind::Identifier) || !self.is_definition(self.index) {
             let expression = self.parse_expression(Precedence::None)?;
             let location = expression.location.clone();
             return Ok(Statement::Expression { expression }.at(location));
         }
 
         let name = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
-
         self.advance();
+        let mut clauses = vec![self.parse_equation(&name)?];
+        while !clauses[0].0.is_empty() && self.continues_definition_of(&name) {
+            while try_consume_any!(self, TokenKind::Newline) {}
+            self.advance();
+            clauses.push(self.parse_equation(&name)?);
+        }
 
-        if self.current_is(TokenKind::Identifier) {
-            let mut parameters = vec![];
-
-            while let Some(t) = self.current() {
-                if !t.node.kind.is_primary() {
-                    break;
-                }
-                parameters.push(t);
-                self.advance();
+        let (parameters, body) = match clauses.pop() {
+            Some(clause)
+                if clauses.is_empty()
+                    && clause
+                        .0
+                        .iter()
+                        .all(|parameter| parameter.node.kind == TokenKind::Identifier) =>
+            {
+                clause
+            }
+            Some(clause) => {
+                clauses.push(clause);
+                Self::merge_equations(&name, clauses)?
             }
+            None => return Err(ErrorKind::UnexpectedEndOfFile.into()),
+        };
+        let location = body.location.clone();
+        if parameters.is_empty() {
+            Ok(Statement::Variable { name, body }.at(location))
+        } else {
+            Self::curry_definition(name, parameters, body, location)
+        }
+    }
 
+    fn parse_equation(
+        &mut self,
+        name: &Located<Token>,
+    ) -> Result<(Vec<Located<Token>>, Located<Expression>)> {
+        let mut parameters = vec![];
+        while let Some(t) = self.current() {
+            if !t.node.kind.is_pattern() {
+                break;
+            }
+            parameters.push(t);
             self.advance();
+        }
 
-            let body = self.parse_expression(Precedence::None)?;
-            let location = body.location.clone();
-            Self::curry_definition(name, parameters, body, location)
+        let body = if try_consume_any!(self, TokenKind::Equal) {
+            self.parse_expression(Precedence::None)?
         } else {
-            self.advance();
-            let body = self.parse_expression(Precedence::None)?;
-            let location = body.location.clone();
-            Ok(Statement::Variable { name, body }.at(location))
+            let guarded = std::mem::replace(&mut self.guarded, true);
+            let guards = self.parse_guards(name.location.clone());
+            self.guarded = guarded;
+            guards?
+        };
+        Ok((parameters, body))
+    }
+
+    fn merge_equations(
+        name: &Located<Token>,
+        clauses: Vec<(Vec<Located<Token>>, Located<Expression>)>,
+    ) -> Result<(Vec<Located<Token>>, Located<Expression>)> {
+        let arity = clauses[0].0.len();
+        if arity == 0 {
+            return err!(
+                ErrorKind::MissingParameter,
+                name.location.clone(),
+                "Patterns can only be used on function parameters."
+            );
+        }
+        if let Some((_, body)) = clauses.iter().find(|(patterns, _)| patterns.len() != arity) {
+            return err!(
+                ErrorKind::MissingParameter,
+                body.location.clone(),
+                format!(
+                    "Every equation of {} must have {arity} parameters.",
+                    name.node.get_identifier_name().unwrap_or_default()
+                )
+            );
+        }
+
+        let arguments: Vec<_> = (0..arity)
+            .map(|i| {
+                Token::new(
+                    TokenKind::Identifier,
+                    TokenValue::Identifier(format!("#{i}")),
+                )
+                .at(name.location.clone())
+            })
+            .collect();
+        let body = Expression::Match {
+            arguments: arguments.clone(),
+            clauses,
+        }
+        .at(name.location.clone());
+        Ok((arguments, body))
+    }
+
+    fn parse_guards(&mut self, location: Rc<Location>) -> Result<Located<Expression>> {
+        while try_consume_any!(self, TokenKind::Newline) {}
+        let guards = self
+            .current()
+            .ok_or(ErrorKind::UnexpectedEndOfFile)?
+            .location;
+        let mut branches = vec![];
+        loop {
+            consume!(self, TokenKind::Pipe, location, "| ");
+            if try_consume_any!(self, TokenKind::Else) {
+                consume!(self, TokenKind::Equal, location, "= ");
+                let otherwise = self.parse_expression(Precedence::None)?;
+                return Ok(Expression::If {
+                    branches,
+                    otherwise: Box::new(otherwise),
+                }
+                .at(guards));
+            }
+
+            let condition = self.parse_expression(Precedence::None)?;
+            consume!(self, TokenKind::Equal, condition.location, "= ");
+            branches.push((condition, self.parse_expression(Precedence::None)?));
+
+            if self.continues_on_next_line(TokenKind::Pipe) {
+                while try_consume_any!(self, TokenKind::Newline) {}
+            } else if !self.current_is(TokenKind::Pipe) {
+                return err!(
+                    ErrorKind::IncompleteIf,
+                    location,
+                    "Guarded definitions must end with an `| else = ...` guard."
+                );
+            }
         }
     }
 
@@ -255,7 +657,7 @@ impl Parser {
         .at(location))
     }
 
-    fn parse_lambda(&mut self) -> Result<Located<Expression>> {
+    fn parse_lambda(&mut self, separator: TokenKind) -> Result<Located<Expression>> {
         let mut parameters = vec![];
         let mut location = self
             .current()
@@ -269,7 +671,14 @@ impl Parser {
             self.advance();
         }
 
-        consume!(self, TokenKind::Dollar, location);
+        if parameters.is_empty() {
+            return err!(
+                ErrorKind::MissingParameter,
+                location,
+                "Expected a parameter name after \\."
+            );
+        }
+        consume!(self, separator, location);
 
         let body = self.parse_expression(Precedence::None)?;
 
@@ -288,13 +697,36 @@ impl Parser {
     fn parse_expression(&mut self, precedence: Precedence) -> Result<Located<Expression>> {
         let mut left = self.parse_prefix()?;
 
-        while !self.is_eof() && !self.is_end_of_expression() {
+        loop {
+            if precedence < Precedence::Pipeline
+                && self.continues_on_next_line(TokenKind::PipeGreater)
+            {
+                while try_consume_any!(self, TokenKind::Newline) {}
+            }
+            if self.is_end_of_expression()
+                || self.guarded
+                    && (self.current_is(TokenKind::Pipe) || self.current_is(TokenKind::Equal))
+            {
+                break;
+            }
+
             if self.current_is(TokenKind::If) && Precedence::Conditional > precedence {
                 self.advance();
                 left = self.parse_if()?;
                 continue;
             }
 
+            if let Some(token) = self.current()
+                && token.node.kind == TokenKind::Equal
+            {
+                return Err(Error::with_help(
+                    ErrorKind::UnexpectedToken,
+                    token.location.clone(),
+                    "= can only be used in definitions, did you mean ==?",
+                )
+                .suggest(token.location, 1, "=="));
+            }
+
             if let Some(token) = self.current() {
                 let current_precedence = Precedence::from(token.node.kind);
                 if current_precedence > precedence {
@@ -324,7 +756,63 @@ impl Parser {
         Ok(left)
     }
 
+    fn parse_nested(&mut self) -> Result<Located<Expression>> {
+        let guarded = std::mem::take(&mut self.guarded);
+        let expression = self.parse_expression(Precedence::None);
+        self.guarded = guarded;
+        expression
+    }
+
     fn parse_prefix(&mut self) -> Result<Located<Expression>> {
+        let mut expression = self.parse_primary()?;
+        while try_consume_any!(self, TokenKind::Dot) {
+            let field = self.expect_identifier("Expected a field name after `.`.")?;
+            let location = field.location.clone();
+            expression = Expression::Field {
+                record: Box::new(expression),
+                field,
+            }
+            .at(location);
+        }
+        Ok(expression)
+    }
+
+    fn parse_record(&mut self, location: Rc<Location>) -> Result<Located<Expression>> {
+        let mut fields: Vec<(Located<Token>, Located<Expression>)> = vec![];
+        let field = self.current_is(TokenKind::Identifier) && self.next_is(1, TokenKind::Colon);
+        if !field && !self.current_is(TokenKind::RightBrace) && !self.current_is(TokenKind::Newline)
+        {
+            return err!(
+                ErrorKind::ExpectedExpression,
+                location,
+                "Braces start a record of name: value fields, use do ... end for a block."
+            );
+        }
+        if !self.current_is(TokenKind::RightBrace) {
+            loop {
+                let name = self.expect_identifier("Expected a field name in record.")?;
+                if fields
+                    .iter()
+                    .any(|(field, _)| field.node.value == name.node.value)
+                {
+                    return err!(
+                        ErrorKind::InvalidIdentifier,
+                        name.location,
+                        "Duplicate field in record."
+                    );
+                }
+                consume!(self, TokenKind::Colon, name.location, ": ");
+                fields.push((name, self.parse_nested()?));
+                if !try_consume_any!(self, TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+        consume!(self, TokenKind::RightBrace, location, "}");
+        Ok(Expression::Record { fields }.at(location))
+    }
+
+    fn parse_primary(&mut self) -> Result<Located<Expression>> {
         let token = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
         let location = token.location.clone();
 
@@ -346,7 +834,7 @@ impl Parser {
                         .find(|t| t.node.kind != TokenKind::Identifier)
                         .is_some_and(|t| t.node.kind == TokenKind::Dollar)
                 {
-                    self.parse_lambda()
+                    self.parse_lambda(TokenKind::Dollar)
                 } else {
                     self.advance();
                     Ok(Expression::Identifier { token }.at(location))
@@ -355,9 +843,42 @@ impl Parser {
 
             TokenKind::LeftParenthesis => {
                 self.advance();
-                let expression = self.parse_expression(Precedence::None)?;
-                consume!(self, TokenKind::RightParenthesis, location);
-                Ok(expression)
+                let mut expressions = vec![self.parse_nested()?];
+                while try_consume_any!(self, TokenKind::Semicolon)
+                    && !self.current_is(TokenKind::RightParenthesis)
+                {
+                    expressions.push(self.parse_nested()?);
+                }
+                consume!(self, TokenKind::RightParenthesis, location, ")");
+                if expressions.len() == 1 {
+                    return Ok(expressions.remove(0));
+                }
+                let statements = expressions
+                    .into_iter()
+                    .map(|expression| {
+                        let location = expression.location.clone();
+                        Statement::Expression { expression }.at(location)
+                    })
+                    .collect();
+                Ok(Expression::Block { statements }.at(location))
+            }
+            TokenKind::LeftBracket => {
+                self.advance();
+                let mut elements = vec![];
+                if !self.current_is(TokenKind::RightBracket) {
+                    loop {
+                        elements.push(self.parse_nested()?);
+                        if !try_consume_any!(self, TokenKind::Comma) {
+                            break;
+                        }
+                    }
+                }
+                consume!(self, TokenKind::RightBracket, location, "]");
+                Ok(Expression::List { elements }.at(location))
+            }
+            TokenKind::LeftBrace => {
+                self.advance();
+                self.parse_record(location)
             }
             TokenKind::True
             | TokenKind::False
@@ -373,6 +894,18 @@ impl Parser {
                 self.advance();
                 self.parse_if()
             }
+            TokenKind::Let => {
+                self.advance();
+                self.parse_let(location)
+            }
+            TokenKind::Do => {
+                self.advance();
+                self.parse_block(location)
+            }
+            TokenKind::Backslash => {
+                self.advance();
+                self.parse_lambda(TokenKind::Arrow)
+            }
             _ => err!(
                 ErrorKind::ExpectedExpression,
                 location,
@@ -389,9 +922,20 @@ impl Parser {
         let operator = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
         self.advance();
 
-        let right = self.parse_expression(precedence)?;
+        let right = if operator.node.kind == TokenKind::Colon {
+            self.parse_expression(Precedence::Comparison)?
+        } else {
+            self.parse_expression(precedence)?
+        };
         let location = operator.location.clone();
 
+        if operator.node.kind == TokenKind::PipeGreater {
+            return Ok(Expression::Call {
+                function: Box::new(right),
+                argument: Box::new(left),
+            }
+            .at(location));
+        }
         Ok(Expression::Binary {
             left: Box::new(left),
             operator,
@@ -400,12 +944,64 @@ impl Parser {
         .at(location))
     }
 
+    fn parse_block(&mut self, location: Rc<Location>) -> Result<Located<Expression>> {
+        let statements = self.parse_sequence(&location, "do block", |parser| {
+            let guarded = std::mem::take(&mut parser.guarded);
+            let statement = parser.parse_definition();
+            parser.guarded = guarded;
+            statement
+        })?;
+        if !statements
+            .last()
+            .is_some_and(|statement| matches!(statement.node, Statement::Expression { .. }))
+        {
+            return err!(
+                ErrorKind::ExpectedExpression,
+                statements
+                    .last()
+                    .map_or(location, |statement| statement.location.clone()),
+                "A do block must end with an expression."
+            );
+        }
+        Ok(Expression::Block { statements }.at(location))
+    }
+
+    fn parse_let(&mut self, location: Rc<Location>) -> Result<Located<Expression>> {
+        let name = self.expect_identifier("Expected a name after let.")?;
+        let mut parameters = vec![];
+        while self.current_is(TokenKind::Identifier) {
+            parameters.push(self.expect_identifier("Expected a parameter name.")?);
+        }
+        consume!(self, TokenKind::Equal, name.location, "= ");
+        let value = self.parse_expression(Precedence::None)?;
+        consume!(self, TokenKind::In, location, " in ");
+        let body = self.parse_expression(Precedence::None)?;
+
+        if parameters.is_empty() {
+            return Ok(Expression::Let {
+                name,
+                value: Box::new(value),
+                body: Box::new(body),
+            }
+            .at(location));
+        }
+        let definition = Self::curry_definition(name, parameters, value, location.clone())?;
+        let body_location = body.location.clone();
+        Ok(Expression::Block {
+            statements: vec![
+                definition,
+                Statement::Expression { expression: body }.at(body_location),
+            ],
+        }
+        .at(location))
+    }
+
     fn parse_if(&mut self) -> Result<Located<Expression>> {
         let token = self.current().ok_or(ErrorKind::UnexpectedEndOfFile)?;
         let location = token.location;
         let condition = self.parse_expression(Precedence::None)?;
 
-        consume!(self, TokenKind::Then, location);
+        consume!(self, TokenKind::Then, location, "then ");
 
         let body = self.parse_expression(Precedence::None)?;
 
@@ -414,13 +1010,13 @@ impl Parser {
             self.advance();
             let condition = self.parse_expression(Precedence::None)?;
 
-            consume!(self, TokenKind::Then, location);
+            consume!(self, TokenKind::Then, location, "then ");
 
             let body = self.parse_expression(Precedence::None)?;
             branches.push((condition, body));
         }
 
-        consume!(self, TokenKind::Else, location);
+        consume!(self, TokenKind::Else, location, "else ");
 
         Ok(Expression::If {
             branches,
@@ -5,7 +5,9 @@ pub enum Precedence {
     None,
     Definition,
     Conditional,
+    Pipeline,
     Comparison,
+    Cons,
     Term,
     Factor,
     Exponentiation,
@@ -21,12 +23,14 @@ impl From<TokenKind> for Precedence {
         match kind {
             TokenKind::Equal => Self::Definition,
             TokenKind::If => Self::Conditional,
+            TokenKind::PipeGreater => Self::Pipeline,
             TokenKind::BangEqual
             | TokenKind::EqualEqual
             | TokenKind::Less
             | TokenKind::LessEqual
             | TokenKind::Greater
             | TokenKind::GreaterEqual => Self::Comparison,
+            TokenKind::Colon => Self::Cons,
             TokenKind::Plus | TokenKind::Minus => Self::Term,
             TokenKind::Star | TokenKind::Slash | TokenKind::Percent => Self::Factor,
             TokenKind::StarStar => Self::Exponentiation,
@@ -0,0 +1,326 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::interpreter::Environment;
+use crate::model::{Expression, Located, Location, Statement, Token, TokenValue};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Builtin,
+    Free,
+    Global,
+    Parameter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SymbolKind {
+    Constructor,
+    Declaration,
+    Function,
+    Newtype,
+    Parameter,
+    Variable,
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub location: Rc<Location>,
+}
+
+#[derive(Debug)]
+pub struct Reference {
+    pub name: String,
+    pub location: Rc<Location>,
+    pub binding: Binding,
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Resolution {
+    pub declarations: Vec<Symbol>,
+    pub definitions: Vec<Symbol>,
+    pub deprecations: HashMap<String, Option<String>>,
+    pub imports: Vec<Vec<Located<Token>>>,
+    pub parameters: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+impl Resolution {
+    pub fn defines(&self, name: &str) -> bool {
+        self.definitions.iter().any(|symbol| symbol.name == name)
+    }
+}
+
+pub fn resolve(statements: &[Located<Statement>]) -> Resolution {
+    let mut resolver = Resolver {
+        builtins: Environment::new(),
+        resolution: Resolution::default(),
+        parameters: vec![],
+        scope: None,
+    };
+    resolver.builtins.borrow_mut().add_builtins();
+
+    for statement in statements {
+        resolver.declare(statement);
+    }
+    for statement in statements {
+        resolver.statement(statement);
+    }
+    resolver.resolution
+}
+
+pub fn import_path(current_file: Option<&Path>, path: &[Located<Token>]) -> Result<PathBuf> {
+    let mut relative_path = String::new();
+    for (i, part) in path.iter().enumerate() {
+        if let TokenValue::Identifier(ref s) = part.node.value {
+            if i > 0 {
+                relative_path.push('/');
+            }
+            relative_path.push_str(s);
+        } else {
+            return Err(Error::with_help(
+                ErrorKind::InvalidArguments,
+                part.location.clone(),
+                "Import path must be identifiers",
+            ));
+        }
+    }
+    relative_path.push_str(".icy");
+
+    let base_dir = current_file
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."));
+    Ok(base_dir.join(&relative_path))
+}
+
+impl Symbol {
+    fn from_token(token: &Located<Token>, kind: SymbolKind) -> Option<Self> {
+        Some(Self {
+            name: token.node.get_identifier_name()?,
+            kind,
+            location: token.location.clone(),
+        })
+    }
+}
+
+struct Resolver {
+    builtins: Rc<RefCell<Environment>>,
+    resolution: Resolution,
+    parameters: Vec<String>,
+    scope: Option<String>,
+}
+
+impl Resolver {
+    fn declare(&mut self, statement: &Located<Statement>) {
+        match &statement.node {
+            Statement::Attributed {
+                attributes,
+                statement,
+            } => {
+                let names = match &statement.node {
+                    Statement::Definition { name, .. } | Statement::Variable { name, .. } => {
+                        std::slice::from_ref(name)
+                    }
+                    Statement::Data { constructors, .. } => constructors.as_slice(),
+                    _ => &[],
+                };
+                for attribute in attributes.iter().filter(|a| a.is("deprecated")) {
+                    let message = attribute
+                        .arguments
+                        .first()
+                        .and_then(|argument| String::try_from(argument).ok());
+                    for name in names
+                        .iter()
+                        .filter_map(|name| name.node.get_identifier_name())
+                    {
+                        self.resolution.deprecations.insert(name, message.clone());
+                    }
+                }
+                self.declare(statement);
+            }
+            Statement::Definition { name, .. } => self.define(name, SymbolKind::Function),
+            Statement::Data { constructors, .. } => {
+                for constructor in constructors {
+                    self.define(constructor, SymbolKind::Constructor);
+                }
+            }
+            Statement::Newtype { name } => self.define(name, SymbolKind::Newtype),
+            Statement::Variable { name, .. } => self.define(name, SymbolKind::Variable),
+            Statement::Declaration { name, .. } => {
+                if let Some(symbol) = Symbol::from_token(name, SymbolKind::Declaration) {
+                    self.resolution.declarations.push(symbol);
+                }
+            }
+            Statement::Mutual { definitions } => {
+                for definition in definitions {
+                    self.declare(definition);
+                }
+            }
+            Statement::Use { path } => self.resolution.imports.push(path.clone()),
+            Statement::Expression { .. } | Statement::Pragma { .. } => {}
+        }
+    }
+
+    fn define(&mut self, name: &Located<Token>, kind: SymbolKind) {
+        if let Some(symbol) = Symbol::from_token(name, kind) {
+            self.resolution.definitions.push(symbol);
+        }
+    }
+
+    fn statement(&mut self, statement: &Located<Statement>) {
+        match &statement.node {
+            Statement::Attributed { statement, .. } => self.statement(statement),
+            Statement::Definition {
+                name,
+                parameter,
+                body,
+            } => {
+                self.scope = name.node.get_identifier_name();
+                self.bind(parameter, body);
+                self.scope = None;
+            }
+            Statement::Variable { name, body } => {
+                self.scope = name.node.get_identifier_name();
+                self.expression(body);
+                self.scope = None;
+            }
+            Statement::Expression { expression } => self.expression(expression),
+            Statement::Mutual { definitions } => {
+                for definition in definitions {
+                    self.statement(definition);
+                }
+            }
+            Statement::Data { .. }
+            | Statement::Declaration { .. }
+            | Statement::Newtype { .. }
+            | Statement::Pragma { .. }
+            | Statement::Use { .. } => {}
+        }
+    }
+
+    fn bind(&mut self, parameter: &Located<Token>, body: &Located<Expression>) {
+        let symbol = Symbol::from_token(parameter, SymbolKind::Parameter);
+        if let Some(symbol) = &symbol {
+            self.parameters.push(symbol.name.clone());
+        }
+        self.expression(body);
+        if let Some(symbol) = symbol {
+            self.parameters.pop();
+            self.resolution.parameters.push(symbol);
+        }
+    }
+
+    fn expression(&mut self, expression: &Located<Expression>) {
+        match &expression.node {
+            Expression::Unary { expression, .. } => self.expression(expression),
+            Expression::Binary { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            Expression::Call { function, argument } => {
+                self.expression(function);
+                self.expression(argument);
+            }
+            Expression::Identifier { token } => self.reference(token),
+            Expression::If {
+                branches,
+                otherwise,
+            } => {
+                for (condition, body) in branches {
+                    self.expression(condition);
+                    self.expression(body);
+                }
+                self.expression(otherwise);
+            }
+            Expression::Lambda { parameter, body } => self.bind(parameter, body),
+            Expression::Let { name, value, body } => {
+                self.expression(value);
+                self.bind(name, body);
+            }
+            Expression::Block { statements } => self.block(statements),
+            Expression::List { elements } => {
+                for element in elements {
+                    self.expression(element);
+                }
+            }
+            Expression::Record { fields } => {
+                for (_, value) in fields {
+                    self.expression(value);
+                }
+            }
+            Expression::Field { record, .. } => self.expression(record),
+            Expression::Match { clauses, .. } => {
+                for (patterns, body) in clauses {
+                    let symbols: Vec<_> = patterns
+                        .iter()
+                        .filter_map(|pattern| Symbol::from_token(pattern, SymbolKind::Parameter))
+                        .collect();
+                    self.parameters
+                        .extend(symbols.iter().map(|symbol| symbol.name.clone()));
+                    self.expression(body);
+                    self.parameters
+                        .truncate(self.parameters.len() - symbols.len());
+                    self.resolution.parameters.extend(symbols);
+                }
+            }
+            Expression::Literal { .. } => {}
+        }
+    }
+
+    fn block(&mut self, statements: &[Located<Statement>]) {
+        let symbols: Vec<_> = statements
+            .iter()
+            .filter_map(|statement| match &statement.node {
+                Statement::Definition { name, .. } => {
+                    Symbol::from_token(name, SymbolKind::Function)
+                }
+                Statement::Variable { name, .. } => Symbol::from_token(name, SymbolKind::Variable),
+                _ => None,
+            })
+            .collect();
+        self.parameters
+            .extend(symbols.iter().map(|symbol| symbol.name.clone()));
+        for statement in statements {
+            match &statement.node {
+                Statement::Definition {
+                    parameter, body, ..
+                } => self.bind(parameter, body),
+                Statement::Variable {
+                    body: expression, ..
+                }
+                | Statement::Expression { expression } => self.expression(expression),
+                _ => {}
+            }
+        }
+        self.parameters
+            .truncate(self.parameters.len() - symbols.len());
+        self.resolution.parameters.extend(symbols);
+    }
+
+    fn reference(&mut self, token: &Located<Token>) {
+        let Some(name) = token.node.get_identifier_name() else {
+            return;
+        };
+
+        let binding = if self.parameters.contains(&name) {
+            Binding::Parameter
+        } else if self.resolution.defines(&name) {
+            Binding::Global
+        } else if self.builtins.borrow().get(&name).is_some() {
+            Binding::Builtin
+        } else {
+            Binding::Free
+        };
+
+        self.resolution.references.push(Reference {
+            name,
+            location: token.location.clone(),
+            binding,
+            scope: self.scope.clone(),
+        });
+    }
+}